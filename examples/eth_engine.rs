@@ -2,12 +2,14 @@ use alloy_evm::eth::EthEvmFactory;
 use reth::revm::{
     context::TxEnv,
     database_interface::EmptyDB,
-    primitives::{Address, Bytes, TxKind, U256},
+    primitives::{hardfork::SpecId, Address, Bytes, TxKind, U256},
     State,
 };
+use reth_chainspec::{ChainSpecBuilder, EthereumHardfork, ForkCondition};
 use reth_evm_test_harness::{
     evm::{dev_account, dev_account_at, DevHarness, EvmTestHarness},
 };
+use std::sync::Arc;
 
 fn main() -> eyre::Result<()> {
     // Create dev harness with pre-funded test accounts (10,000 ETH each)
@@ -15,7 +17,7 @@ fn main() -> eyre::Result<()> {
 
     // Run all tests
     test_balance_and_transfer(&mut harness)?;
-    test_fork_transition(&mut harness)?;
+    test_fork_transition()?;
     test_custom_tx_type(&mut harness)?;
 
     println!("✓ All tests passed");
@@ -33,7 +35,7 @@ fn test_balance_and_transfer(
 
     // Execute a basic transfer
     let tx = create_transfer_tx();
-    let result = harness.execute_tx(tx)?;
+    let result = harness.send_tx(tx)?;
     assert!(result.is_success());
     assert_eq!(result.gas_used, 21_000);
 
@@ -47,7 +49,7 @@ fn test_balance_and_transfer(
     assert_eq!(sender_balance_after, sender_balance_before - transfer_amount - gas_cost);
     assert_eq!(receiver_balance_after, receiver_balance_before + transfer_amount);
 
-    // Test a precompile (identity at 0x04) using a different account to avoid nonce issues
+    // Test a precompile (identity at 0x04), the sender's nonce is filled from state
     let identity = Address::with_last_byte(4); // Identity precompile
     let input = Bytes::from(vec![1, 2, 3, 4, 5]);
     let result = harness.execute_precompile(identity, input.clone(), Some(receiver))?;
@@ -61,26 +63,29 @@ fn test_balance_and_transfer(
     Ok(())
 }
 
-fn test_fork_transition(
-    harness: &mut EvmTestHarness<State<EmptyDB>, EthEvmFactory>,
-) -> eyre::Result<()> {
+fn test_fork_transition() -> eyre::Result<()> {
     let fork_block = 200;
 
-    // Reset base fee to allow 1 gwei transactions
+    // Berlin chain that activates London at `fork_block`
+    let chain_spec = ChainSpecBuilder::mainnet()
+        .berlin_activated()
+        .with_fork(EthereumHardfork::London, ForkCondition::Block(fork_block))
+        .build();
+    let mut harness = EvmTestHarness::<_, EthEvmFactory>::dev_with_chain_spec(Arc::new(chain_spec));
     harness.set_base_fee(1_000_000_000);
 
     // Pre-fork
     harness.set_block_number(fork_block - 1);
-    let tx_pre = create_test_tx_with_nonce(1);
-    let result_pre = harness.execute_tx(tx_pre)?;
+    assert_eq!(harness.spec_id(), SpecId::BERLIN);
+    let result_pre = harness.send_tx(create_test_tx())?;
 
-    // Post-fork (need different nonce since state persists)
+    // Post-fork (send_tx picks up the nonce bumped by the pre-fork transaction)
     harness.set_block_number(fork_block);
-    let tx_post = create_test_tx_with_nonce(2);
-    let result_post = harness.execute_tx(tx_post)?;
+    assert_eq!(harness.spec_id(), SpecId::LONDON);
+    let result_post = harness.send_tx(create_test_tx())?;
 
-    // Compare behavior across fork
-    assert!(result_pre.is_success());
+    // BASEFEE only exists from London on, so the same init code halts before the fork
+    assert!(!result_pre.is_success());
     assert!(result_post.is_success());
 
     Ok(())
@@ -97,13 +102,11 @@ fn test_custom_tx_type(
         gas_price: 1_000_000_000u128,
         kind: TxKind::Call(Address::with_last_byte(1)),
         data: Bytes::from(vec![0x01, 0x02, 0x03]),
-        nonce: 3, // Account was used in previous tests
-        chain_id: Some(1),
         ..Default::default()
     };
 
     // Test execution (may fail if custom type not supported, which is expected)
-    let result = harness.execute_tx(custom_tx);
+    let result = harness.send_tx(custom_tx);
 
     // Add assertions for your custom transaction behavior here
     // For this example, we just check it executes
@@ -123,32 +126,18 @@ fn create_transfer_tx() -> TxEnv {
         kind: TxKind::Call(dev_account_at(1)), // Send to second dev account
         value: U256::from(1_000_000_000_000_000_000u64), // 1 ETH
         data: Bytes::new(),
-        nonce: 0,
-        chain_id: Some(1),
-        access_list: Default::default(),
-        gas_priority_fee: None,
-        blob_hashes: vec![],
-        max_fee_per_blob_gas: 0,
-        authorization_list: vec![],
-        tx_type: 0,
+        ..Default::default() // nonce and chain ID are filled in by send_tx
     }
 }
 
-fn create_test_tx_with_nonce(nonce: u64) -> TxEnv {
+fn create_test_tx() -> TxEnv {
     TxEnv {
         caller: dev_account(), // Use funded account
         gas_limit: 100_000,
         gas_price: 1_000_000_000u128,
         kind: TxKind::Create,
         value: U256::ZERO,
-        data: Bytes::from(vec![0x60, 0x80, 0x60, 0x40]), // dumb contract code (no logic)
-        nonce,
-        chain_id: Some(1),
-        access_list: Default::default(),
-        gas_priority_fee: None,
-        blob_hashes: vec![],
-        max_fee_per_blob_gas: 0,
-        authorization_list: vec![],
-        tx_type: 0,
+        data: Bytes::from(vec![0x48, 0x00]), // BASEFEE STOP
+        ..Default::default()
     }
 }
@@ -0,0 +1,229 @@
+//! Read-only database serving a state dump file
+
+use super::CodeCache;
+use crate::fixtures::{AccountState, StateDump};
+use parking_lot::RwLock;
+use reth::revm::{
+    bytecode::Bytecode,
+    database_interface::{DBErrorMarker, DatabaseRef},
+    db::CacheDB,
+    primitives::{keccak256, Address, B256, U256},
+    state::AccountInfo,
+};
+use serde_json::value::RawValue;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::Arc,
+};
+
+/// Error reading a state dump
+#[derive(Debug, thiserror::Error)]
+#[error("State dump error: {0}")]
+pub struct StateDumpError(String);
+
+impl DBErrorMarker for StateDumpError {}
+
+/// A decoded account with its storage
+#[derive(Debug)]
+struct DumpedAccount {
+    info: AccountInfo,
+    storage: BTreeMap<U256, U256>,
+}
+
+/// Database serving accounts, code and storage from a state dump
+///
+/// The dump is a [`StateDump`], as written by `EvmTestHarness::dump_state` or anvil's
+/// `anvil_dumpState`. Accounts are only decoded when first read. The database is
+/// read-only; use [`Self::overlay`] to keep writes in memory and leave the dump untouched.
+#[derive(Debug)]
+pub struct StateDumpDb {
+    raw: BTreeMap<Address, Box<RawValue>>,
+    accounts: RwLock<HashMap<Address, Arc<DumpedAccount>>>,
+    contracts: CodeCache,
+}
+
+impl StateDumpDb {
+    /// Load a state dump from a JSON file
+    pub fn from_file(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| crate::Error::fixture(format!("{}: {}", path.display(), e)))?;
+        Self::from_json(&content)
+    }
+
+    /// Load a state dump from a JSON string
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        let dump: StateDump<Box<RawValue>> = serde_json::from_str(json)
+            .map_err(|e| crate::Error::fixture(format!("Failed to parse state dump: {}", e)))?;
+
+        Ok(Self {
+            raw: dump.accounts,
+            accounts: RwLock::new(HashMap::new()),
+            contracts: CodeCache::default(),
+        })
+    }
+
+    /// Layer in-memory writes over the dump
+    pub fn overlay(self) -> CacheDB<Arc<Self>> {
+        super::overlay(self)
+    }
+
+    /// Number of accounts in the dump
+    pub fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Check if the dump contains no accounts
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    /// Decode an account on first access
+    fn account(&self, address: Address) -> Result<Option<Arc<DumpedAccount>>, StateDumpError> {
+        if let Some(account) = self.accounts.read().get(&address) {
+            return Ok(Some(account.clone()));
+        }
+        let Some(raw) = self.raw.get(&address) else {
+            return Ok(None);
+        };
+
+        let state: AccountState = serde_json::from_str(raw.get())
+            .map_err(|e| StateDumpError(format!("Invalid account {}: {}", address, e)))?;
+
+        let info = self
+            .contracts
+            .account_info(address, state.balance, state.nonce, state.code)
+            .map_err(StateDumpError)?;
+
+        let account = Arc::new(DumpedAccount {
+            info,
+            storage: state.storage,
+        });
+        self.accounts.write().insert(address, account.clone());
+
+        Ok(Some(account))
+    }
+}
+
+impl DatabaseRef for StateDumpDb {
+    type Error = StateDumpError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.account(address)?.map(|account| account.info.clone()))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.contracts
+            .code_by_hash(code_hash)
+            .map_err(StateDumpError)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Ok(self
+            .account(address)?
+            .and_then(|account| account.storage.get(&index).copied())
+            .unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        // Dumps carry no block hashes, so match `EmptyDB`
+        Ok(keccak256(number.to_string().as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{
+        dev_account, dev_chain_spec,
+        test_utils::{CONTRACT, STORE_ONE},
+        DevHarness, EvmTestHarness,
+    };
+    use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::{database_interface::EmptyDB, State};
+
+    const DUMP: &str = r#"{
+        "accounts": {
+            "0x00000000000000000000000000000000000000aa": {
+                "balance": "0x64",
+                "nonce": 3,
+                "code": "0x6001600055",
+                "storage": { "0x1": "0x2a" }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_serves_accounts_from_dump() {
+        let db = StateDumpDb::from_json(DUMP).unwrap();
+        let account = Address::with_last_byte(0xaa);
+
+        let info = db.basic_ref(account).unwrap().unwrap();
+
+        assert_eq!(info.balance, U256::from(100));
+        assert_eq!(info.nonce, 3);
+        assert_eq!(
+            db.code_by_hash_ref(info.code_hash)
+                .unwrap()
+                .original_bytes(),
+            info.code.unwrap().original_bytes()
+        );
+        assert_eq!(
+            db.storage_ref(account, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+        assert!(db.basic_ref(Address::ZERO).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_writes_land_in_overlay() {
+        let overlay = StateDumpDb::from_json(DUMP).unwrap().overlay();
+        let dump = overlay.db.clone();
+        let mut harness = EvmTestHarness::new(EthEvmFactory::default(), overlay, dev_chain_spec());
+        let account = Address::with_last_byte(0xaa);
+
+        harness
+            .set_storage(account, U256::from(1), U256::from(7))
+            .unwrap();
+
+        assert_eq!(
+            harness.get_storage(account, U256::from(1)).unwrap(),
+            U256::from(7)
+        );
+        assert_eq!(
+            dump.storage_ref(account, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+    }
+
+    #[test]
+    fn test_reads_harness_dump() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        harness.set_code(CONTRACT, STORE_ONE.to_vec()).unwrap();
+        harness
+            .set_storage(CONTRACT, U256::from(1), U256::from(42))
+            .unwrap();
+
+        let db = StateDumpDb::from_json(&harness.dump_state().to_json().unwrap()).unwrap();
+
+        assert_eq!(db.len(), harness.dump_state().accounts.len());
+        assert_eq!(
+            db.basic_ref(dev_account()).unwrap().unwrap().balance,
+            harness.get_balance(dev_account()).unwrap()
+        );
+        assert_eq!(
+            db.basic_ref(CONTRACT)
+                .unwrap()
+                .unwrap()
+                .code
+                .unwrap()
+                .original_bytes(),
+            STORE_ONE.to_vec()
+        );
+        assert_eq!(
+            db.storage_ref(CONTRACT, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Lazily forked state from a JSON-RPC endpoint, with record/replay cassettes
+
+use super::CodeCache;
+use crate::fixtures::{FixtureManager, RpcCassette};
+use parking_lot::Mutex;
+use reth::revm::{
+    bytecode::Bytecode,
+    database_interface::{DBErrorMarker, DatabaseRef},
+    db::CacheDB,
+    primitives::{Address, Bytes, B256, U256, U64},
+    state::AccountInfo,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Error fetching forked state
+#[derive(Debug, thiserror::Error)]
+#[error("Fork error: {0}")]
+pub struct ForkDbError(String);
+
+impl DBErrorMarker for ForkDbError {}
+
+/// JSON-RPC endpoint used in record mode
+#[derive(Debug)]
+struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+    next_id: AtomicU64,
+}
+
+impl RpcClient {
+    fn request(&self, method: &str, params: &Value) -> Result<Value, ForkDbError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+            .map_err(|e| ForkDbError(format!("{} request failed: {}", method, e)))?
+            .into_string()
+            .map_err(|e| ForkDbError(format!("{} response unreadable: {}", method, e)))?;
+
+        let mut response: Value = serde_json::from_str(&response)
+            .map_err(|e| ForkDbError(format!("{} response is not JSON: {}", method, e)))?;
+        if let Some(error) = response.get("error") {
+            return Err(ForkDbError(format!(
+                "{} returned an error: {}",
+                method, error
+            )));
+        }
+
+        response
+            .get_mut("result")
+            .map(Value::take)
+            .ok_or_else(|| ForkDbError(format!("{} response has no result", method)))
+    }
+}
+
+/// Database fetching accounts, storage and block hashes on demand from a JSON-RPC node
+///
+/// Every response is kept in a cassette that is written through a [`FixtureManager`] when
+/// a database created with [`Self::record`] is dropped. Call [`Self::save_cassette`] to
+/// write it earlier or to handle write errors, which the drop ignores. A database created
+/// with [`Self::replay`] serves only from the cassette and fails on anything that was not
+/// recorded, so CI never touches the network. Use [`Self::overlay`] to keep writes in
+/// memory.
+#[derive(Debug)]
+pub struct ForkDb {
+    client: Option<RpcClient>,
+    fixtures: FixtureManager,
+    name: String,
+    cassette: Mutex<RpcCassette>,
+    contracts: CodeCache,
+}
+
+impl ForkDb {
+    /// Fork from `url` at `block_number`, recording responses to the cassette `name`
+    ///
+    /// Responses already in an existing cassette for the same block are reused.
+    pub fn record(
+        url: impl Into<String>,
+        block_number: u64,
+        fixtures: FixtureManager,
+        name: impl Into<String>,
+    ) -> crate::Result<Self> {
+        let name = name.into();
+        let cassette = fixtures
+            .load_cassette(&name)?
+            .filter(|cassette| cassette.block_number == block_number)
+            .unwrap_or_else(|| RpcCassette {
+                block_number,
+                ..Default::default()
+            });
+
+        let client = RpcClient {
+            url: url.into(),
+            agent: ureq::Agent::new(),
+            next_id: AtomicU64::new(1),
+        };
+
+        Ok(Self::new(Some(client), fixtures, name, cassette))
+    }
+
+    /// Serve state only from the recorded cassette `name`
+    pub fn replay(fixtures: FixtureManager, name: impl Into<String>) -> crate::Result<Self> {
+        let name = name.into();
+        let cassette = fixtures.load_cassette(&name)?.ok_or_else(|| {
+            crate::Error::fixture(format!("Cassette {} has not been recorded", name))
+        })?;
+
+        Ok(Self::new(None, fixtures, name, cassette))
+    }
+
+    fn new(
+        client: Option<RpcClient>,
+        fixtures: FixtureManager,
+        name: String,
+        cassette: RpcCassette,
+    ) -> Self {
+        Self {
+            client,
+            fixtures,
+            name,
+            cassette: Mutex::new(cassette),
+            contracts: CodeCache::default(),
+        }
+    }
+
+    /// Layer in-memory writes over the fork
+    pub fn overlay(self) -> CacheDB<Arc<Self>> {
+        super::overlay(self)
+    }
+
+    /// Block number the state is forked at
+    pub fn block_number(&self) -> u64 {
+        self.cassette.lock().block_number
+    }
+
+    /// Write the recorded responses to the cassette file
+    pub fn save_cassette(&self) -> crate::Result<()> {
+        self.fixtures
+            .save_cassette(&self.name, &self.cassette.lock())
+    }
+
+    /// Serve a request from the cassette, fetching and recording it in record mode
+    fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, ForkDbError> {
+        let key = format!("{}:{}", method, params);
+
+        let recorded = self.cassette.lock().responses.get(&key).cloned();
+        let value = match (recorded, &self.client) {
+            (Some(value), _) => value,
+            (None, Some(client)) => {
+                let value = client.request(method, &params)?;
+                self.cassette
+                    .lock()
+                    .responses
+                    .insert(key.clone(), value.clone());
+                value
+            }
+            (None, None) => {
+                return Err(ForkDbError(format!("{} is not in the cassette", key)));
+            }
+        };
+
+        serde_json::from_value(value)
+            .map_err(|e| ForkDbError(format!("Unexpected response to {}: {}", key, e)))
+    }
+
+    /// Block tag used for state queries
+    fn block_tag(&self) -> String {
+        format!("{:#x}", self.block_number())
+    }
+}
+
+impl Drop for ForkDb {
+    fn drop(&mut self) {
+        if self.client.is_some() {
+            let _ = self.save_cassette();
+        }
+    }
+}
+
+impl DatabaseRef for ForkDb {
+    type Error = ForkDbError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let block = self.block_tag();
+        let balance: U256 = self.request("eth_getBalance", json!([address, block]))?;
+        let nonce: U64 = self.request("eth_getTransactionCount", json!([address, block]))?;
+        let code: Bytes = self.request("eth_getCode", json!([address, block]))?;
+        if balance.is_zero() && nonce.is_zero() && code.is_empty() {
+            return Ok(None);
+        }
+
+        self.contracts
+            .account_info(address, balance, nonce.to(), Some(code))
+            .map(Some)
+            .map_err(ForkDbError)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // Code is fetched with its account, there is no RPC method to look it up by hash
+        self.contracts.code_by_hash(code_hash).map_err(ForkDbError)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let block = self.block_tag();
+        self.request("eth_getStorageAt", json!([address, index, block]))
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let block: Value = self.request(
+            "eth_getBlockByNumber",
+            json!([format!("{:#x}", number), false]),
+        )?;
+        serde_json::from_value(block["hash"].clone())
+            .map_err(|e| ForkDbError(format!("Block {} has no hash: {}", number, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::atomic::AtomicUsize,
+    };
+
+    /// Serve canned JSON-RPC responses on a local port, counting requests
+    fn mock_node(requests: Arc<AtomicUsize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                requests.fetch_add(1, Ordering::SeqCst);
+
+                let result = match request["method"].as_str().unwrap() {
+                    "eth_getBalance" => json!("0x64"),
+                    "eth_getTransactionCount" => json!("0x2"),
+                    "eth_getCode" => json!("0x6000"),
+                    "eth_getStorageAt" => json!(B256::with_last_byte(42)),
+                    "eth_getBlockByNumber" => json!({ "hash": B256::with_last_byte(7) }),
+                    method => panic!("unexpected method {}", method),
+                };
+                let response =
+                    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+
+        url
+    }
+
+    #[test]
+    fn test_record_then_replay() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let url = mock_node(requests.clone());
+        let dir = std::env::temp_dir().join(format!("fork-cassette-{}", std::process::id()));
+        let account = Address::with_last_byte(0xaa);
+
+        let db = ForkDb::record(url, 100, FixtureManager::new(&dir), "mainnet").unwrap();
+        let info = db.basic_ref(account).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(100));
+        assert_eq!(info.nonce, 2);
+        assert_eq!(
+            db.storage_ref(account, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+        assert_eq!(db.block_hash_ref(99).unwrap(), B256::with_last_byte(7));
+        // Repeated reads are served from the cassette
+        db.basic_ref(account).unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+        db.save_cassette().unwrap();
+
+        let db = ForkDb::replay(FixtureManager::new(&dir), "mainnet").unwrap();
+        assert_eq!(db.basic_ref(account).unwrap(), Some(info));
+        assert_eq!(
+            db.storage_ref(account, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+        assert!(db.storage_ref(account, U256::from(2)).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cassette_saved_on_drop() {
+        let url = mock_node(Arc::new(AtomicUsize::new(0)));
+        let dir = std::env::temp_dir().join(format!("fork-drop-{}", std::process::id()));
+        let account = Address::with_last_byte(0xaa);
+
+        let db = ForkDb::record(url, 100, FixtureManager::new(&dir), "mainnet")
+            .unwrap()
+            .overlay();
+        db.storage_ref(account, U256::from(1)).unwrap();
+        drop(db);
+
+        let db = ForkDb::replay(FixtureManager::new(&dir), "mainnet").unwrap();
+        assert_eq!(
+            db.storage_ref(account, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
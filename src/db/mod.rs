@@ -0,0 +1,78 @@
+//! Database backends for testing against captured chain state
+
+#[cfg(feature = "fixtures")]
+mod dump;
+#[cfg(feature = "fork")]
+mod fork;
+
+#[cfg(feature = "fixtures")]
+pub use dump::{StateDumpDb, StateDumpError};
+#[cfg(feature = "fork")]
+pub use fork::{ForkDb, ForkDbError};
+
+#[cfg(feature = "fixtures")]
+use parking_lot::RwLock;
+#[cfg(feature = "fixtures")]
+use reth::revm::{
+    bytecode::Bytecode,
+    db::CacheDB,
+    primitives::{Address, Bytes, B256, KECCAK_EMPTY, U256},
+    state::AccountInfo,
+};
+#[cfg(feature = "fixtures")]
+use std::{collections::HashMap, sync::Arc};
+
+/// Wrap a read-only database in a `CacheDB` that keeps writes in memory
+///
+/// The wrapped database is shared, so it can still be read after the overlay is written.
+#[cfg(feature = "fixtures")]
+fn overlay<DB>(db: DB) -> CacheDB<Arc<DB>> {
+    CacheDB::new(Arc::new(db))
+}
+
+/// Code of the accounts loaded so far, for backends that can only serve code by hash
+/// after loading the account holding it
+#[cfg(feature = "fixtures")]
+#[derive(Debug, Default)]
+struct CodeCache(RwLock<HashMap<B256, Bytecode>>);
+
+#[cfg(feature = "fixtures")]
+impl CodeCache {
+    /// Build the info of a loaded account, remembering its code
+    fn account_info(
+        &self,
+        address: Address,
+        balance: U256,
+        nonce: u64,
+        code: Option<Bytes>,
+    ) -> Result<AccountInfo, String> {
+        let mut info = AccountInfo {
+            balance,
+            nonce,
+            code_hash: KECCAK_EMPTY,
+            code: None,
+        };
+        if let Some(code) = code.filter(|code| !code.is_empty()) {
+            let bytecode = Bytecode::new_raw_checked(code)
+                .map_err(|e| format!("Invalid code for {}: {}", address, e))?;
+            info.code_hash = bytecode.hash_slow();
+            self.0.write().insert(info.code_hash, bytecode.clone());
+            info.code = Some(bytecode);
+        }
+
+        Ok(info)
+    }
+
+    /// Get code by hash, failing for code whose account has not been loaded
+    fn code_by_hash(&self, code_hash: B256) -> Result<Bytecode, String> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::default());
+        }
+
+        self.0
+            .read()
+            .get(&code_hash)
+            .cloned()
+            .ok_or_else(|| format!("Code not loaded for hash {}", code_hash))
+    }
+}
@@ -0,0 +1,165 @@
+//! ABI-aware contract calls using `sol!` generated types
+
+use super::{EvmTestHarness, HarnessExecutionResult};
+use crate::{Error, Result};
+use alloy_sol_types::{SolCall, SolError, SolEvent};
+use reth::revm::{
+    context::TxEnv, context_interface::result::HaltReason, database_interface::DatabaseCommit,
+    primitives::hardfork::SpecId,
+};
+use reth_evm::{Database, EvmFactory};
+
+impl HarnessExecutionResult {
+    /// Decode the return value of a successful call
+    ///
+    /// Fails with the revert reason if the call did not succeed.
+    pub fn decode_return<C: SolCall>(&self) -> Result<C::Return> {
+        if let Some(reason) = &self.revert_reason {
+            return Err(Error::evm_execution(format!(
+                "{} failed: {}",
+                C::SIGNATURE,
+                reason
+            )));
+        }
+
+        C::abi_decode_returns(&self.output).map_err(|e| {
+            Error::evm_execution(format!("Failed to decode {} return: {}", C::SIGNATURE, e))
+        })
+    }
+
+    /// Decode the revert data as the custom error `E`
+    ///
+    /// Returns `None` if the call succeeded or reverted with a different error.
+    pub fn decode_revert<E: SolError>(&self) -> Option<E> {
+        if self.success {
+            return None;
+        }
+
+        E::abi_decode(&self.output).ok()
+    }
+
+    /// Decode every log emitted as the event `E`, in emission order
+    ///
+    /// Logs of other events are skipped. Anonymous events carry no signature topic,
+    /// so any log that decodes as `E` is returned.
+    pub fn decode_events<E: SolEvent>(&self) -> Result<Vec<E>> {
+        let mut events = Vec::new();
+
+        for log in &self.logs {
+            if E::ANONYMOUS {
+                events.extend(E::decode_log_data(&log.data).ok());
+            } else if log.topics().first() == Some(&E::SIGNATURE_HASH) {
+                let event = E::decode_log_data(&log.data).map_err(|e| {
+                    Error::evm_execution(format!("Failed to decode {}: {}", E::SIGNATURE, e))
+                })?;
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// ABI-encode `call` into the transaction input and execute it without committing
+    ///
+    /// Uses the same `eth_call` semantics as [`Self::call`].
+    pub fn call_sol<C: SolCall>(
+        &mut self,
+        mut tx: TxEnv,
+        call: &C,
+    ) -> Result<HarnessExecutionResult> {
+        tx.data = call.abi_encode().into();
+        self.call(tx)
+    }
+
+    /// ABI-encode `call` into the transaction input, then fill and commit it
+    ///
+    /// See [`Self::send_tx`] for how the transaction is filled.
+    pub fn send_sol<C: SolCall>(
+        &mut self,
+        mut tx: TxEnv,
+        call: &C,
+    ) -> Result<HarnessExecutionResult> {
+        tx.data = call.abi_encode().into();
+        self.send_tx(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::test_utils::{call_tx, dev_harness_with_code, CONTRACT};
+    use alloy_sol_types::sol;
+    use reth::revm::primitives::U256;
+
+    sol! {
+        function store(uint256 value) returns (uint256);
+        event Stored(uint256 value);
+        error TooLow(uint256 value);
+    }
+
+    #[test]
+    fn test_sol_call_decodes_return_and_events() {
+        // Store the first argument in slot 0, emit it as `Stored` and return it
+        let mut code = vec![
+            0x60, 0x04, 0x35, 0x80, 0x60, 0x00, 0x55, 0x80, 0x60, 0x00, 0x52, 0x7f,
+        ];
+        code.extend_from_slice(Stored::SIGNATURE_HASH.as_slice());
+        code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xa1, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+        let mut harness = dev_harness_with_code(&code);
+        let call = storeCall {
+            value: U256::from(7),
+        };
+
+        let view = harness.call_sol(call_tx(CONTRACT), &call).unwrap();
+        assert_eq!(view.decode_return::<storeCall>().unwrap(), U256::from(7));
+        assert_eq!(
+            harness.get_storage(CONTRACT, U256::ZERO).unwrap(),
+            U256::ZERO
+        );
+
+        let result = harness.send_sol(call_tx(CONTRACT), &call).unwrap();
+        let events = result.decode_events::<Stored>().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].value, U256::from(7));
+        assert_eq!(
+            harness.get_storage(CONTRACT, U256::ZERO).unwrap(),
+            U256::from(7)
+        );
+    }
+
+    #[test]
+    fn test_sol_call_decodes_custom_error() {
+        let error = TooLow {
+            value: U256::from(3),
+        }
+        .abi_encode();
+        // CODECOPY the error data that follows the code, then REVERT with it
+        let len = error.len() as u8;
+        let mut code = vec![
+            0x60, len, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xfd,
+        ];
+        code.extend_from_slice(&error);
+        let mut harness = dev_harness_with_code(&code);
+
+        let result = harness
+            .call_sol(
+                call_tx(CONTRACT),
+                &storeCall {
+                    value: U256::from(3),
+                },
+            )
+            .unwrap();
+
+        assert!(result.decode_return::<storeCall>().is_err());
+        assert_eq!(
+            result.decode_revert::<TooLow>().map(|e| e.value),
+            Some(U256::from(3))
+        );
+    }
+}
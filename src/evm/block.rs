@@ -0,0 +1,157 @@
+//! Full block execution through reth's block executor
+
+use super::{spec::blob_excess_gas_and_price, EvmTestHarness};
+use crate::{Error, Result};
+use alloy_consensus::BlockHeader;
+use reth::revm::{
+    context::TxEnv,
+    context_interface::result::HaltReason,
+    database_interface::DatabaseCommit,
+    db::BundleState,
+    primitives::{hardfork::SpecId, U256},
+    state::{Account, EvmState, EvmStorageSlot},
+};
+use reth_evm::{
+    execute::{BlockExecutionOutput, Executor},
+    ConfigureEvm, Database, EvmFactory,
+};
+use reth_primitives_traits::{BlockTy, ReceiptTy, RecoveredBlock};
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// Execute a full block with reth's block executor and commit the resulting state
+    ///
+    /// Unlike `execute_tx`, this runs the pre-block system calls (EIP-4788, EIP-2935),
+    /// withdrawals, block rewards and post-block request extraction of the given
+    /// `ConfigureEvm`. The harness block environment is moved to the executed block's
+    /// number, timestamp, gas limit, beneficiary, difficulty, prevrandao, base fee and
+    /// blob gas price.
+    pub fn execute_block<C: ConfigureEvm>(
+        &mut self,
+        evm_config: &C,
+        block: &RecoveredBlock<BlockTy<C::Primitives>>,
+    ) -> Result<BlockExecutionOutput<ReceiptTy<C::Primitives>>> {
+        let output = evm_config
+            .executor(self.db_mut())
+            .execute(block)
+            .map_err(|e| Error::evm_execution(format!("Block execution failed: {}", e)))?;
+
+        self.commit_accounts(bundle_to_evm_state(&output.state))?;
+
+        let header = block.header();
+        let mut block_env = self.block_env().clone();
+        block_env.number = U256::from(header.number());
+        block_env.timestamp = U256::from(header.timestamp());
+        block_env.gas_limit = header.gas_limit();
+        block_env.beneficiary = header.beneficiary();
+        block_env.difficulty = header.difficulty();
+        block_env.prevrandao = header.mix_hash();
+        if let Some(base_fee) = header.base_fee_per_gas() {
+            block_env.basefee = base_fee;
+        }
+        block_env.blob_excess_gas_and_price = blob_excess_gas_and_price(
+            self.chain_spec(),
+            header.excess_blob_gas(),
+            header.timestamp(),
+        );
+        self.set_block_env(block_env);
+
+        Ok(output)
+    }
+}
+
+/// Convert the changes recorded in a bundle into committable EVM state
+fn bundle_to_evm_state(bundle: &BundleState) -> EvmState {
+    bundle
+        .state
+        .iter()
+        .map(|(address, bundle_account)| {
+            let mut info = bundle_account.info.clone().unwrap_or_default();
+            if info.code.is_none() {
+                info.code = bundle.contracts.get(&info.code_hash).cloned();
+            }
+
+            let mut account = Account::from(info);
+            account.storage = bundle_account
+                .storage
+                .iter()
+                .map(|(slot, value)| {
+                    let slot_value = EvmStorageSlot::new_changed(
+                        value.previous_or_original_value,
+                        value.present_value,
+                        0,
+                    );
+                    (*slot, slot_value)
+                })
+                .collect();
+
+            if bundle_account.info.is_none() {
+                account.mark_selfdestruct();
+            } else if bundle_account.was_destroyed() {
+                // Recreated after being destroyed, so previous storage must be wiped
+                account.mark_created();
+            }
+            account.mark_touch();
+
+            (*address, account)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{dev_chain_spec, DevHarness};
+    use alloy_consensus::{BlockBody, Header};
+    use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::{
+        database_interface::EmptyDB,
+        primitives::{Address, B256},
+        State,
+    };
+    use reth_ethereum_primitives::Block;
+    use reth_evm_ethereum::EthEvmConfig;
+
+    #[test]
+    fn test_execute_empty_block() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let block = Block {
+            header: Header {
+                number: 1,
+                timestamp: 12,
+                gas_limit: 30_000_000,
+                beneficiary: Address::with_last_byte(0xbe),
+                mix_hash: B256::with_last_byte(1),
+                parent_beacon_block_root: Some(B256::ZERO),
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(10_000_000),
+                ..Default::default()
+            },
+            body: BlockBody::default(),
+        };
+        let block = RecoveredBlock::new_unhashed(block, Vec::new());
+
+        let output = harness
+            .execute_block(&EthEvmConfig::new(dev_chain_spec()), &block)
+            .unwrap();
+
+        assert!(output.result.receipts.is_empty());
+        assert_eq!(output.result.gas_used, 0);
+        assert_eq!(harness.block_number(), 1);
+        assert_eq!(harness.block_env().gas_limit, 30_000_000);
+        assert_eq!(
+            harness.block_env().beneficiary,
+            Address::with_last_byte(0xbe)
+        );
+        assert_eq!(
+            harness.block_env().prevrandao,
+            Some(B256::with_last_byte(1))
+        );
+        let blob_price = harness.block_env().blob_excess_gas_and_price.unwrap();
+        assert_eq!(blob_price.excess_blob_gas, 10_000_000);
+        assert!(blob_price.blob_gasprice > 1);
+    }
+}
@@ -0,0 +1,61 @@
+//! Geth `callTracer`-style call trees
+
+use super::{EvmTestHarness, HarnessExecutionResult};
+use crate::Result;
+use alloy_rpc_types_trace::geth::CallConfig;
+use reth::revm::{
+    context::TxEnv, context_interface::result::HaltReason, database_interface::DatabaseCommit,
+    primitives::hardfork::SpecId, Inspector,
+};
+use reth_evm::{Database, EvmFactory};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+where
+    TracingInspector: for<'a> Inspector<Evm::Context<&'a mut DB>>,
+{
+    /// Execute a transaction and commit it, recording its call tree into the result
+    ///
+    /// Frames include logs; the tree serializes to the JSON of geth's `callTracer`.
+    pub fn execute_tx_call_traced(&mut self, tx: TxEnv) -> Result<HarnessExecutionResult> {
+        let config = CallConfig::default().with_log();
+        let inspector =
+            TracingInspector::new(TracingInspectorConfig::from_geth_call_config(&config));
+
+        let (result, inspector) = self.execute_tx_with_inspector(tx, inspector)?;
+        let call_trace = inspector
+            .into_geth_builder()
+            .geth_call_traces(config, result.gas_used);
+
+        Ok(result.with_call_trace(call_trace))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evm::{
+        dev_account,
+        test_utils::{call_tx, dev_harness_with_code, CONTRACT, STATICCALL_IDENTITY},
+    };
+    use reth::revm::primitives::Address;
+
+    #[test]
+    fn test_call_trace_records_nested_precompile_call() {
+        let mut harness = dev_harness_with_code(&STATICCALL_IDENTITY);
+        let identity = Address::with_last_byte(4);
+        let tx = harness.fill_tx(call_tx(CONTRACT)).unwrap();
+
+        let result = harness.execute_tx_call_traced(tx).unwrap();
+
+        let root = result.call_trace.unwrap();
+        assert_eq!(root.typ, "CALL");
+        assert_eq!(root.from, dev_account());
+        assert_eq!(root.to, Some(CONTRACT));
+        assert_eq!(root.calls.len(), 1);
+        assert_eq!(root.calls[0].typ, "STATICCALL");
+        assert_eq!(root.calls[0].to, Some(identity));
+    }
+}
@@ -0,0 +1,252 @@
+//! Direct state manipulation, equivalent to anvil's `setBalance`/`setCode`/`setStorageAt`
+
+use super::EvmTestHarness;
+use crate::{Error, Result};
+use reth::revm::{
+    bytecode::Bytecode,
+    context::TxEnv,
+    context_interface::result::HaltReason,
+    database_interface::{DatabaseCommit, DatabaseRef},
+    db::{AccountState, AccountStatus, CacheDB, PlainAccount},
+    primitives::{hardfork::SpecId, Address, Bytes, U256},
+    state::AccountInfo,
+    State,
+};
+use reth_evm::{Database, EvmFactory};
+
+/// Database whose cached accounts can be written directly
+///
+/// Writes bypass `commit`, so EIP-161 state clearing cannot drop them, for example
+/// storage set on an otherwise empty account.
+pub trait CheatDb: Database {
+    /// Load an account into the cache, creating it if missing, and apply a change to it
+    fn modify_account_info(
+        &mut self,
+        address: Address,
+        f: impl FnOnce(&mut AccountInfo),
+    ) -> std::result::Result<(), Self::Error>;
+
+    /// Write a storage slot, creating the account if missing
+    fn insert_storage(
+        &mut self,
+        address: Address,
+        slot: U256,
+        value: U256,
+    ) -> std::result::Result<(), Self::Error>;
+
+    /// Remove an account together with its storage
+    fn remove_account(&mut self, address: Address) -> std::result::Result<(), Self::Error>;
+}
+
+/// Load a cached `State` account, creating an empty one if it does not exist
+fn plain_account<DB: Database>(
+    state: &mut State<DB>,
+    address: Address,
+) -> std::result::Result<&mut PlainAccount, DB::Error> {
+    let account = state.load_cache_account(address)?;
+    if account.account.is_none() {
+        // A missing or destroyed account has no storage left to load
+        account.status = AccountStatus::InMemoryChange;
+    }
+    Ok(account.account.get_or_insert_with(PlainAccount::default))
+}
+
+impl<DB: Database> CheatDb for State<DB> {
+    fn modify_account_info(
+        &mut self,
+        address: Address,
+        f: impl FnOnce(&mut AccountInfo),
+    ) -> std::result::Result<(), Self::Error> {
+        let info = &mut plain_account(self, address)?.info;
+        f(info);
+        if let Some(code) = info.code.clone() {
+            let code_hash = info.code_hash;
+            self.cache.contracts.insert(code_hash, code);
+        }
+        Ok(())
+    }
+
+    fn insert_storage(
+        &mut self,
+        address: Address,
+        slot: U256,
+        value: U256,
+    ) -> std::result::Result<(), Self::Error> {
+        plain_account(self, address)?.storage.insert(slot, value);
+        Ok(())
+    }
+
+    fn remove_account(&mut self, address: Address) -> std::result::Result<(), Self::Error> {
+        let account = self.load_cache_account(address)?;
+        account.account = None;
+        account.status = AccountStatus::Destroyed;
+        Ok(())
+    }
+}
+
+impl<ExtDB: DatabaseRef> CheatDb for CacheDB<ExtDB>
+where
+    Self: Database,
+{
+    fn modify_account_info(
+        &mut self,
+        address: Address,
+        f: impl FnOnce(&mut AccountInfo),
+    ) -> std::result::Result<(), Self::Error> {
+        let account = self.load_account(address)?;
+        f(&mut account.info);
+        if matches!(account.account_state, AccountState::NotExisting) {
+            account.account_state = AccountState::StorageCleared;
+        }
+        if let Some(code) = account.info.code.clone() {
+            let code_hash = account.info.code_hash;
+            self.cache.contracts.insert(code_hash, code);
+        }
+        Ok(())
+    }
+
+    fn insert_storage(
+        &mut self,
+        address: Address,
+        slot: U256,
+        value: U256,
+    ) -> std::result::Result<(), Self::Error> {
+        let account = self.load_account(address)?;
+        account.storage.insert(slot, value);
+        if matches!(account.account_state, AccountState::NotExisting) {
+            account.account_state = AccountState::StorageCleared;
+        }
+        Ok(())
+    }
+
+    fn remove_account(&mut self, address: Address) -> std::result::Result<(), Self::Error> {
+        let account = self.load_account(address)?;
+        account.info = AccountInfo::default();
+        account.storage.clear();
+        account.account_state = AccountState::NotExisting;
+        Ok(())
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit + CheatDb,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// Set the balance of an account
+    pub fn set_balance(&mut self, address: Address, balance: U256) -> Result<()> {
+        self.modify_account(address, |info| info.balance = balance)
+    }
+
+    /// Set the nonce of an account
+    pub fn set_nonce(&mut self, address: Address, nonce: u64) -> Result<()> {
+        self.modify_account(address, |info| info.nonce = nonce)
+    }
+
+    /// Set the runtime code of an account, replacing any existing code
+    ///
+    /// Fails if the code starts with the EIP-7702 delegation prefix but is not a valid
+    /// delegation.
+    pub fn set_code(&mut self, address: Address, code: impl Into<Bytes>) -> Result<()> {
+        let bytecode = Bytecode::new_raw_checked(code.into())
+            .map_err(|e| Error::evm_execution(format!("Invalid bytecode: {}", e)))?;
+        self.modify_account(address, |info| {
+            info.code_hash = bytecode.hash_slow();
+            info.code = Some(bytecode);
+        })
+    }
+
+    /// Set a storage slot of an account
+    pub fn set_storage(&mut self, address: Address, slot: U256, value: U256) -> Result<()> {
+        self.db_mut()
+            .insert_storage(address, slot, value)
+            .map_err(|e| Error::evm_execution(format!("Failed to set storage: {:?}", e)))
+    }
+
+    /// Remove an account, wiping its balance, nonce, code and storage
+    pub fn clear_account(&mut self, address: Address) -> Result<()> {
+        self.db_mut()
+            .remove_account(address)
+            .map_err(|e| Error::evm_execution(format!("Failed to clear account: {:?}", e)))
+    }
+
+    /// Load an account and apply a change to its info
+    fn modify_account(&mut self, address: Address, f: impl FnOnce(&mut AccountInfo)) -> Result<()> {
+        self.db_mut()
+            .modify_account_info(address, f)
+            .map_err(|e| Error::evm_execution(format!("Failed to load account: {:?}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{
+        dev_chain_spec,
+        test_utils::{call_tx, dev_harness_with_code, CONTRACT, RETURN_SLOT_0},
+        DevHarness,
+    };
+    use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::database_interface::EmptyDB;
+
+    fn assert_storage_on_fresh_account<DB: Database + DatabaseCommit + CheatDb>(db: DB) {
+        let mut harness = EvmTestHarness::new(EthEvmFactory::default(), db, dev_chain_spec());
+        let account = Address::with_last_byte(0xaa);
+
+        harness
+            .set_storage(account, U256::from(1), U256::from(7))
+            .unwrap();
+
+        assert_eq!(
+            harness.get_storage(account, U256::from(1)).unwrap(),
+            U256::from(7)
+        );
+        assert_eq!(
+            harness.get_storage(account, U256::ZERO).unwrap(),
+            U256::ZERO
+        );
+    }
+
+    #[test]
+    fn test_set_balance_and_nonce() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let account = Address::with_last_byte(0xaa);
+
+        harness.set_balance(account, U256::from(42)).unwrap();
+        harness.set_nonce(account, 7).unwrap();
+
+        assert_eq!(harness.get_balance(account).unwrap(), U256::from(42));
+        assert_eq!(harness.get_nonce(account).unwrap(), 7);
+
+        harness.clear_account(account).unwrap();
+
+        assert_eq!(harness.get_balance(account).unwrap(), U256::ZERO);
+        assert_eq!(harness.get_nonce(account).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_set_code_and_storage() {
+        let mut harness = dev_harness_with_code(&RETURN_SLOT_0);
+
+        harness
+            .set_storage(CONTRACT, U256::ZERO, U256::from(0x1234))
+            .unwrap();
+
+        let result = harness.call(call_tx(CONTRACT)).unwrap();
+
+        assert_eq!(U256::from_be_slice(&result.output), U256::from(0x1234));
+    }
+
+    #[test]
+    fn test_set_storage_on_fresh_account() {
+        assert_storage_on_fresh_account(State::builder().with_database(EmptyDB::default()).build());
+        assert_storage_on_fresh_account(CacheDB::new(EmptyDB::default()));
+    }
+
+    #[test]
+    fn test_set_code_rejects_invalid_delegation() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+
+        assert!(harness.set_code(CONTRACT, vec![0xef, 0x01, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+//! Contract address derivation for CREATE and CREATE2
+
+use super::EvmTestHarness;
+use crate::Result;
+use reth::revm::{
+    context::TxEnv,
+    context_interface::result::HaltReason,
+    database_interface::DatabaseCommit,
+    primitives::{hardfork::SpecId, Address, B256},
+};
+use reth_evm::{Database, EvmFactory};
+
+/// Compute the address of a contract created with CREATE by `deployer` at `nonce`
+pub fn create_address(deployer: Address, nonce: u64) -> Address {
+    deployer.create(nonce)
+}
+
+/// Compute the address of a contract created with CREATE2 by `deployer`
+pub fn create2_address(deployer: Address, salt: B256, init_code: &[u8]) -> Address {
+    deployer.create2_from_code(salt, init_code)
+}
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// Compute the address the next CREATE from `deployer` will deploy to
+    pub fn next_create_address(&mut self, deployer: Address) -> Result<Address> {
+        Ok(create_address(deployer, self.get_nonce(deployer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{dev_account, test_utils::RETURN_EMPTY, DevHarness, TxBuilder};
+    use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::{database_interface::EmptyDB, State};
+
+    #[test]
+    fn test_create_reports_deployed_address() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let expected = harness.next_create_address(dev_account()).unwrap();
+        let tx = TxBuilder::eip1559()
+            .with_caller(dev_account())
+            .with_gas_limit(100_000)
+            .with_create()
+            .with_input(RETURN_EMPTY.to_vec())
+            .build()
+            .unwrap();
+
+        let result = harness.send_tx(tx).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(result.created_address(), Some(expected));
+        assert!(result.state_diff.account(expected).unwrap().created);
+    }
+
+    #[test]
+    fn test_create2_address_matches_eip1014_example() {
+        // Example 1 from EIP-1014
+        let address = create2_address(Address::ZERO, B256::ZERO, &[0x00]);
+
+        assert_eq!(
+            address,
+            "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+}
@@ -2,9 +2,9 @@
 
 use super::EvmTestHarness;
 use reth::revm::{
-    context::TxEnv, database_interface::EmptyDB, primitives::{hardfork::SpecId, Address, KECCAK_EMPTY, U256}, state::AccountInfo, State
+    context::TxEnv, context_interface::result::HaltReason, database_interface::EmptyDB, primitives::{hardfork::SpecId, Address, KECCAK_EMPTY, U256}, state::AccountInfo, State
 };
-use reth_chainspec::ChainSpec;
+use reth_chainspec::{ChainSpec, ChainSpecBuilder};
 use reth_evm::EvmFactory;
 use std::sync::Arc;
 
@@ -25,6 +25,11 @@ pub const DEV_ACCOUNTS: [&str; 10] = [
 /// Default balance for dev accounts (10,000 ETH)
 pub const DEV_BALANCE: u128 = 10_000_000_000_000_000_000_000u128;
 
+/// Chain spec used by dev harnesses: mainnet with every fork up to Cancun active at genesis
+pub fn dev_chain_spec() -> Arc<ChainSpec> {
+    Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build())
+}
+
 /// Create a dev database with pre-funded accounts
 pub fn create_dev_db() -> State<EmptyDB> {
     let mut db = State::builder().with_database(EmptyDB::default()).build();
@@ -53,11 +58,11 @@ pub trait DevHarness<Evm: EvmFactory> {
     fn dev_with_chain_spec(chain_spec: Arc<ChainSpec>) -> Self;
 }
 
-impl<Evm: EvmFactory<Spec = SpecId, Tx = TxEnv> + Default>
+impl<Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>> + Default>
     DevHarness<Evm> for EvmTestHarness<State<EmptyDB>, Evm>
 {
     fn dev() -> Self {
-        Self::dev_with_chain_spec(Arc::new(ChainSpec::default()))
+        Self::dev_with_chain_spec(dev_chain_spec())
     }
 
     fn dev_with_chain_spec(chain_spec: Arc<ChainSpec>) -> Self {
@@ -0,0 +1,186 @@
+//! Per-transaction state diffs
+
+use reth::revm::{
+    primitives::{Address, Bytes, KECCAK_EMPTY, U256},
+    state::{AccountInfo, EvmState},
+};
+use reth_evm::Database;
+use std::collections::BTreeMap;
+
+/// A value before and after a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    /// Value before the transaction
+    pub before: T,
+    /// Value after the transaction
+    pub after: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    /// Create a change, returning `None` if the value is unchanged
+    pub fn new(before: T, after: T) -> Option<Self> {
+        (before != after).then_some(Self { before, after })
+    }
+}
+
+/// Changes made to a single account
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    /// Balance change
+    pub balance: Option<Change<U256>>,
+    /// Nonce change
+    pub nonce: Option<Change<u64>>,
+    /// Code change (empty bytes for accounts without code)
+    pub code: Option<Change<Bytes>>,
+    /// Changed storage slots
+    pub storage: BTreeMap<U256, Change<U256>>,
+    /// Whether the account was created by the transaction
+    pub created: bool,
+    /// Whether the account was selfdestructed by the transaction
+    pub selfdestructed: bool,
+}
+
+impl AccountDiff {
+    /// Check if the diff records no changes
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none()
+            && self.nonce.is_none()
+            && self.code.is_none()
+            && self.storage.is_empty()
+            && !self.created
+            && !self.selfdestructed
+    }
+}
+
+/// State changes made by a single transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Changed accounts, keyed by address
+    pub accounts: BTreeMap<Address, AccountDiff>,
+}
+
+impl StateDiff {
+    /// Compute the diff between the database and uncommitted EVM state
+    ///
+    /// Must be called before `state` is committed to `db`.
+    pub fn compute<DB: Database>(db: &mut DB, state: &EvmState) -> Result<Self, DB::Error> {
+        let mut accounts = BTreeMap::new();
+
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue;
+            }
+
+            let before = db.basic(*address)?.unwrap_or_default();
+            // Accounts can be loaded without their code, so only load it when the hash moved
+            let code = if before.code_hash == account.info.code_hash {
+                None
+            } else {
+                Change::new(code_bytes(db, &before)?, code_bytes(db, &account.info)?)
+            };
+
+            let diff = AccountDiff {
+                balance: Change::new(before.balance, account.info.balance),
+                nonce: Change::new(before.nonce, account.info.nonce),
+                code,
+                storage: account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(key, slot)| {
+                        let change = Change {
+                            before: slot.original_value,
+                            after: slot.present_value,
+                        };
+                        (*key, change)
+                    })
+                    .collect(),
+                created: account.is_created(),
+                selfdestructed: account.is_selfdestructed(),
+            };
+
+            if !diff.is_empty() {
+                accounts.insert(*address, diff);
+            }
+        }
+
+        Ok(Self { accounts })
+    }
+
+    /// Get the diff for an account
+    pub fn account(&self, address: Address) -> Option<&AccountDiff> {
+        self.accounts.get(&address)
+    }
+
+    /// Get the change to a storage slot
+    pub fn storage_change(&self, address: Address, slot: U256) -> Option<&Change<U256>> {
+        self.accounts.get(&address)?.storage.get(&slot)
+    }
+
+    /// Addresses of accounts created by the transaction
+    pub fn created(&self) -> impl Iterator<Item = Address> + '_ {
+        self.accounts
+            .iter()
+            .filter(|(_, diff)| diff.created)
+            .map(|(address, _)| *address)
+    }
+
+    /// Addresses of accounts selfdestructed by the transaction
+    pub fn selfdestructed(&self) -> impl Iterator<Item = Address> + '_ {
+        self.accounts
+            .iter()
+            .filter(|(_, diff)| diff.selfdestructed)
+            .map(|(address, _)| *address)
+    }
+
+    /// Check if the transaction changed no state
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+/// Get the code of an account, loading it by hash if needed, or empty bytes for no code
+fn code_bytes<DB: Database>(db: &mut DB, info: &AccountInfo) -> Result<Bytes, DB::Error> {
+    if info.code_hash == KECCAK_EMPTY {
+        return Ok(Bytes::new());
+    }
+
+    match &info.code {
+        Some(code) => Ok(code.original_bytes()),
+        None => Ok(db.code_by_hash(info.code_hash)?.original_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth::revm::{
+        bytecode::Bytecode, database_interface::EmptyDB, db::CacheDB, state::Account,
+    };
+
+    #[test]
+    fn test_account_loaded_without_code_keeps_code() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let contract = Address::with_last_byte(0xcc);
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[0x00]));
+        let mut info = AccountInfo {
+            code_hash: bytecode.hash_slow(),
+            code: Some(bytecode),
+            ..Default::default()
+        };
+        db.insert_account_info(contract, info.clone());
+
+        // Credited like a block beneficiary, with the code left unloaded
+        info.balance = U256::from(1);
+        info.code = None;
+        let mut account = Account::from(info);
+        account.mark_touch();
+        let state = [(contract, account)].into_iter().collect();
+
+        let diff = StateDiff::compute(&mut db, &state).unwrap();
+
+        let account_diff = diff.account(contract).unwrap();
+        assert!(account_diff.balance.is_some());
+        assert!(account_diff.code.is_none());
+    }
+}
@@ -0,0 +1,487 @@
+//! Typed differences between a test and a reference execution result
+
+use super::{
+    diff::{AccountDiff, Change},
+    result::HarnessExecutionResult,
+    revert::RevertReason,
+};
+use reth::revm::primitives::{Address, Bytes, B256, U256};
+use std::{collections::BTreeSet, fmt};
+
+/// A single way in which a test result differs from the reference result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// Success status differs
+    Status {
+        /// Value from the test EVM
+        test: bool,
+        /// Value from the reference EVM
+        reference: bool,
+    },
+    /// Gas used differs
+    GasUsed {
+        /// Value from the test EVM
+        test: u64,
+        /// Value from the reference EVM
+        reference: u64,
+    },
+    /// Gas refunded differs
+    GasRefunded {
+        /// Value from the test EVM
+        test: u64,
+        /// Value from the reference EVM
+        reference: u64,
+    },
+    /// Output data differs
+    Output {
+        /// Value from the test EVM
+        test: Bytes,
+        /// Value from the reference EVM
+        reference: Bytes,
+    },
+    /// Revert or halt reason differs
+    Revert {
+        /// Value from the test EVM
+        test: Option<RevertReason>,
+        /// Value from the reference EVM
+        reference: Option<RevertReason>,
+    },
+    /// Deployed contract address differs
+    CreatedAddress {
+        /// Value from the test EVM
+        test: Option<Address>,
+        /// Value from the reference EVM
+        reference: Option<Address>,
+    },
+    /// Number of logs differs
+    LogCount {
+        /// Value from the test EVM
+        test: usize,
+        /// Value from the reference EVM
+        reference: usize,
+    },
+    /// Emitter of a log differs
+    LogAddress {
+        /// Position of the log in the transaction
+        index: usize,
+        /// Value from the test EVM
+        test: Address,
+        /// Value from the reference EVM
+        reference: Address,
+    },
+    /// Topics of a log differ
+    LogTopics {
+        /// Position of the log in the transaction
+        index: usize,
+        /// Value from the test EVM
+        test: Vec<B256>,
+        /// Value from the reference EVM
+        reference: Vec<B256>,
+    },
+    /// Data of a log differs
+    LogData {
+        /// Position of the log in the transaction
+        index: usize,
+        /// Value from the test EVM
+        test: Bytes,
+        /// Value from the reference EVM
+        reference: Bytes,
+    },
+    /// Balance change of an account differs
+    Balance {
+        /// Account the difference applies to
+        address: Address,
+        /// Value from the test EVM
+        test: Option<Change<U256>>,
+        /// Value from the reference EVM
+        reference: Option<Change<U256>>,
+    },
+    /// Nonce change of an account differs
+    Nonce {
+        /// Account the difference applies to
+        address: Address,
+        /// Value from the test EVM
+        test: Option<Change<u64>>,
+        /// Value from the reference EVM
+        reference: Option<Change<u64>>,
+    },
+    /// Code change of an account differs
+    Code {
+        /// Account the difference applies to
+        address: Address,
+        /// Value from the test EVM
+        test: Option<Change<Bytes>>,
+        /// Value from the reference EVM
+        reference: Option<Change<Bytes>>,
+    },
+    /// Change to a storage slot differs
+    Storage {
+        /// Account the difference applies to
+        address: Address,
+        /// Storage slot that differs
+        slot: U256,
+        /// Value from the test EVM
+        test: Option<Change<U256>>,
+        /// Value from the reference EVM
+        reference: Option<Change<U256>>,
+    },
+    /// Whether an account was created differs
+    Created {
+        /// Account the difference applies to
+        address: Address,
+        /// Value from the test EVM
+        test: bool,
+        /// Value from the reference EVM
+        reference: bool,
+    },
+    /// Whether an account was selfdestructed differs
+    Selfdestructed {
+        /// Account the difference applies to
+        address: Address,
+        /// Value from the test EVM
+        test: bool,
+        /// Value from the reference EVM
+        reference: bool,
+    },
+}
+
+impl Difference {
+    /// Check if this is a gas accounting difference (gas used or refunded)
+    pub fn is_gas(&self) -> bool {
+        matches!(self, Self::GasUsed { .. } | Self::GasRefunded { .. })
+    }
+
+    /// Get the account a state difference applies to
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            Self::Balance { address, .. }
+            | Self::Nonce { address, .. }
+            | Self::Code { address, .. }
+            | Self::Storage { address, .. }
+            | Self::Created { address, .. }
+            | Self::Selfdestructed { address, .. } => Some(*address),
+            _ => None,
+        }
+    }
+}
+
+/// Format an optional change as `before -> after`
+fn change<T: fmt::Display>(change: &Option<Change<T>>) -> String {
+    change.as_ref().map_or_else(
+        || "unchanged".to_string(),
+        |change| format!("{} -> {}", change.before, change.after),
+    )
+}
+
+/// Format an optional value, or `none`
+fn optional<T: fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "none".to_string(), ToString::to_string)
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { test, reference } => {
+                write!(f, "success: test={}, reference={}", test, reference)
+            }
+            Self::GasUsed { test, reference } => write!(
+                f,
+                "gas used: test={}, reference={} ({:+})",
+                test,
+                reference,
+                *test as i128 - *reference as i128
+            ),
+            Self::GasRefunded { test, reference } => write!(
+                f,
+                "gas refunded: test={}, reference={} ({:+})",
+                test,
+                reference,
+                *test as i128 - *reference as i128
+            ),
+            Self::Output { test, reference } => {
+                write!(f, "output: test={}, reference={}", test, reference)
+            }
+            Self::Revert { test, reference } => write!(
+                f,
+                "revert reason: test={}, reference={}",
+                optional(test),
+                optional(reference)
+            ),
+            Self::CreatedAddress { test, reference } => write!(
+                f,
+                "created address: test={}, reference={}",
+                optional(test),
+                optional(reference)
+            ),
+            Self::LogCount { test, reference } => {
+                write!(f, "log count: test={}, reference={}", test, reference)
+            }
+            Self::LogAddress {
+                index,
+                test,
+                reference,
+            } => write!(
+                f,
+                "log {} address: test={}, reference={}",
+                index, test, reference
+            ),
+            Self::LogTopics {
+                index,
+                test,
+                reference,
+            } => write!(
+                f,
+                "log {} topics: test={:?}, reference={:?}",
+                index, test, reference
+            ),
+            Self::LogData {
+                index,
+                test,
+                reference,
+            } => write!(
+                f,
+                "log {} data: test={}, reference={}",
+                index, test, reference
+            ),
+            Self::Balance {
+                address,
+                test,
+                reference,
+            } => write!(
+                f,
+                "{} balance: test={}, reference={}",
+                address,
+                change(test),
+                change(reference)
+            ),
+            Self::Nonce {
+                address,
+                test,
+                reference,
+            } => write!(
+                f,
+                "{} nonce: test={}, reference={}",
+                address,
+                change(test),
+                change(reference)
+            ),
+            Self::Code {
+                address,
+                test,
+                reference,
+            } => write!(
+                f,
+                "{} code: test={}, reference={}",
+                address,
+                change(test),
+                change(reference)
+            ),
+            Self::Storage {
+                address,
+                slot,
+                test,
+                reference,
+            } => write!(
+                f,
+                "{} storage[{:#x}]: test={}, reference={}",
+                address,
+                slot,
+                change(test),
+                change(reference)
+            ),
+            Self::Created {
+                address,
+                test,
+                reference,
+            } => write!(
+                f,
+                "{} created: test={}, reference={}",
+                address, test, reference
+            ),
+            Self::Selfdestructed {
+                address,
+                test,
+                reference,
+            } => write!(
+                f,
+                "{} selfdestructed: test={}, reference={}",
+                address, test, reference
+            ),
+        }
+    }
+}
+
+/// Collect every difference between a test and a reference result
+pub(crate) fn compare(
+    test: &HarnessExecutionResult,
+    reference: &HarnessExecutionResult,
+) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    if test.success != reference.success {
+        differences.push(Difference::Status {
+            test: test.success,
+            reference: reference.success,
+        });
+    }
+
+    if test.gas_used != reference.gas_used {
+        differences.push(Difference::GasUsed {
+            test: test.gas_used,
+            reference: reference.gas_used,
+        });
+    }
+
+    if test.gas_refunded != reference.gas_refunded {
+        differences.push(Difference::GasRefunded {
+            test: test.gas_refunded,
+            reference: reference.gas_refunded,
+        });
+    }
+
+    if test.output != reference.output {
+        differences.push(Difference::Output {
+            test: test.output.clone(),
+            reference: reference.output.clone(),
+        });
+    }
+
+    if test.revert_reason != reference.revert_reason {
+        differences.push(Difference::Revert {
+            test: test.revert_reason.clone(),
+            reference: reference.revert_reason.clone(),
+        });
+    }
+
+    if test.created_address != reference.created_address {
+        differences.push(Difference::CreatedAddress {
+            test: test.created_address,
+            reference: reference.created_address,
+        });
+    }
+
+    if test.logs.len() != reference.logs.len() {
+        differences.push(Difference::LogCount {
+            test: test.logs.len(),
+            reference: reference.logs.len(),
+        });
+    }
+
+    for (index, (test_log, reference_log)) in test.logs.iter().zip(&reference.logs).enumerate() {
+        if test_log.address != reference_log.address {
+            differences.push(Difference::LogAddress {
+                index,
+                test: test_log.address,
+                reference: reference_log.address,
+            });
+        }
+
+        if test_log.topics() != reference_log.topics() {
+            differences.push(Difference::LogTopics {
+                index,
+                test: test_log.topics().to_vec(),
+                reference: reference_log.topics().to_vec(),
+            });
+        }
+
+        if test_log.data.data != reference_log.data.data {
+            differences.push(Difference::LogData {
+                index,
+                test: test_log.data.data.clone(),
+                reference: reference_log.data.data.clone(),
+            });
+        }
+    }
+
+    let addresses: BTreeSet<_> = test
+        .state_diff
+        .accounts
+        .keys()
+        .chain(reference.state_diff.accounts.keys())
+        .copied()
+        .collect();
+    for address in addresses {
+        let test_diff = test
+            .state_diff
+            .account(address)
+            .cloned()
+            .unwrap_or_default();
+        let reference_diff = reference
+            .state_diff
+            .account(address)
+            .cloned()
+            .unwrap_or_default();
+        compare_accounts(address, test_diff, reference_diff, &mut differences);
+    }
+
+    differences
+}
+
+/// Collect the differences between the changes made to one account
+fn compare_accounts(
+    address: Address,
+    test: AccountDiff,
+    reference: AccountDiff,
+    differences: &mut Vec<Difference>,
+) {
+    if test.balance != reference.balance {
+        differences.push(Difference::Balance {
+            address,
+            test: test.balance,
+            reference: reference.balance,
+        });
+    }
+
+    if test.nonce != reference.nonce {
+        differences.push(Difference::Nonce {
+            address,
+            test: test.nonce,
+            reference: reference.nonce,
+        });
+    }
+
+    if test.code != reference.code {
+        differences.push(Difference::Code {
+            address,
+            test: test.code,
+            reference: reference.code,
+        });
+    }
+
+    let slots: BTreeSet<_> = test
+        .storage
+        .keys()
+        .chain(reference.storage.keys())
+        .copied()
+        .collect();
+    for slot in slots {
+        let test_change = test.storage.get(&slot);
+        let reference_change = reference.storage.get(&slot);
+        if test_change != reference_change {
+            differences.push(Difference::Storage {
+                address,
+                slot,
+                test: test_change.cloned(),
+                reference: reference_change.cloned(),
+            });
+        }
+    }
+
+    if test.created != reference.created {
+        differences.push(Difference::Created {
+            address,
+            test: test.created,
+            reference: reference.created,
+        });
+    }
+
+    if test.selfdestructed != reference.selfdestructed {
+        differences.push(Difference::Selfdestructed {
+            address,
+            test: test.selfdestructed,
+            reference: reference.selfdestructed,
+        });
+    }
+}
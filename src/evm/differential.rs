@@ -0,0 +1,326 @@
+//! Differential execution of a custom EVM against a reference EVM
+
+use super::{CheatDb, EvmComparison, EvmTestHarness, HarnessExecutionResult};
+use crate::{Error, Result};
+use reth::revm::{
+    context::TxEnv,
+    context_interface::result::HaltReason,
+    database_interface::DatabaseCommit,
+    primitives::{hardfork::SpecId, Address, Bytes, U256},
+};
+use reth_chainspec::ChainSpec;
+use reth_evm::{Database, EvmFactory};
+use std::sync::Arc;
+
+/// Runs every setup step and transaction on a test and a reference EVM over identical state
+pub struct DifferentialHarness<DB: Database, TestEvm: EvmFactory, RefEvm: EvmFactory> {
+    test: EvmTestHarness<DB, TestEvm>,
+    reference: EvmTestHarness<DB, RefEvm>,
+    stop_at_first_divergence: bool,
+}
+
+impl<
+        DB: Database + DatabaseCommit + Clone,
+        TestEvm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+        RefEvm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > DifferentialHarness<DB, TestEvm, RefEvm>
+{
+    /// Create a differential harness, giving each EVM its own copy of `db`
+    pub fn new(
+        test_factory: TestEvm,
+        reference_factory: RefEvm,
+        db: DB,
+        chain_spec: Arc<ChainSpec>,
+    ) -> Self {
+        Self {
+            test: EvmTestHarness::new(test_factory, db.clone(), chain_spec.clone()),
+            reference: EvmTestHarness::new(reference_factory, db, chain_spec),
+            stop_at_first_divergence: false,
+        }
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit,
+        TestEvm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+        RefEvm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > DifferentialHarness<DB, TestEvm, RefEvm>
+{
+    /// Create a differential harness, building each EVM's database with `make_db`
+    ///
+    /// Works with databases that can't be cloned, such as [`create_dev_db`]'s
+    /// `State<EmptyDB>`. `make_db` must return identical state on every call.
+    ///
+    /// [`create_dev_db`]: crate::evm::create_dev_db
+    pub fn from_db_factory(
+        test_factory: TestEvm,
+        reference_factory: RefEvm,
+        make_db: impl Fn() -> DB,
+        chain_spec: Arc<ChainSpec>,
+    ) -> Self {
+        Self {
+            test: EvmTestHarness::new(test_factory, make_db(), chain_spec.clone()),
+            reference: EvmTestHarness::new(reference_factory, make_db(), chain_spec),
+            stop_at_first_divergence: false,
+        }
+    }
+
+    /// Stop `execute_txs` after the first transaction whose results differ
+    pub fn with_stop_at_first_divergence(mut self) -> Self {
+        self.stop_at_first_divergence = true;
+        self
+    }
+
+    /// Execute a transaction on both EVMs and compare the results
+    ///
+    /// Both EVMs always run the transaction. If exactly one of them rejects it, the
+    /// returned error carries both outcomes; if both reject it, the test EVM's error
+    /// is returned.
+    pub fn execute_tx(&mut self, tx: TxEnv) -> Result<EvmComparison> {
+        let caller = tx.caller;
+        let beneficiary = self.reference.block_env().beneficiary;
+        let test_result = self.test.execute_tx(tx.clone());
+        let reference_result = self.reference.execute_tx(tx);
+
+        match (test_result, reference_result) {
+            (Ok(test_result), Ok(reference_result)) => {
+                let comparison = EvmComparison::new(test_result, reference_result);
+                Ok(comparison.with_fee_accounts(caller, beneficiary))
+            }
+            (Err(err), Err(_)) => Err(err),
+            (Err(err), Ok(result)) => Err(Error::evm_execution(format!(
+                "test EVM rejected a transaction the reference EVM executed: test={}, reference={}",
+                err,
+                outcome(&result)
+            ))),
+            (Ok(result), Err(err)) => Err(Error::evm_execution(format!(
+                "reference EVM rejected a transaction the test EVM executed: test={}, reference={}",
+                outcome(&result),
+                err
+            ))),
+        }
+    }
+
+    /// Fill a transaction from the reference state, then execute it on both EVMs
+    ///
+    /// See [`EvmTestHarness::fill_tx`] for how each field is filled.
+    pub fn send_tx(&mut self, tx: TxEnv) -> Result<EvmComparison> {
+        let tx = self.reference.fill_tx(tx)?;
+        self.execute_tx(tx)
+    }
+
+    /// Execute transactions in order, returning a comparison per executed transaction
+    ///
+    /// With [`Self::with_stop_at_first_divergence`], the last comparison returned is
+    /// the first one that does not match.
+    pub fn execute_txs(
+        &mut self,
+        txs: impl IntoIterator<Item = TxEnv>,
+    ) -> Result<Vec<EvmComparison>> {
+        let mut comparisons = Vec::new();
+
+        for tx in txs {
+            let comparison = self.execute_tx(tx)?;
+            let diverged = !comparison.is_match();
+            comparisons.push(comparison);
+
+            if diverged && self.stop_at_first_divergence {
+                break;
+            }
+        }
+
+        Ok(comparisons)
+    }
+
+    /// Set the block number on both EVMs
+    pub fn set_block_number(&mut self, number: u64) {
+        self.test.set_block_number(number);
+        self.reference.set_block_number(number);
+    }
+
+    /// Set the block timestamp on both EVMs
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.test.set_timestamp(timestamp);
+        self.reference.set_timestamp(timestamp);
+    }
+
+    /// Set the base fee on both EVMs
+    pub fn set_base_fee(&mut self, base_fee: u64) {
+        self.test.set_base_fee(base_fee);
+        self.reference.set_base_fee(base_fee);
+    }
+
+    /// Pin the spec ID on both EVMs
+    pub fn set_spec_id(&mut self, spec_id: SpecId) {
+        self.test.set_spec_id(spec_id);
+        self.reference.set_spec_id(spec_id);
+    }
+
+    /// Get the harness running the test EVM
+    pub fn test(&self) -> &EvmTestHarness<DB, TestEvm> {
+        &self.test
+    }
+
+    /// Get the harness running the test EVM mutably, for setup that only applies to it
+    pub fn test_mut(&mut self) -> &mut EvmTestHarness<DB, TestEvm> {
+        &mut self.test
+    }
+
+    /// Get the harness running the reference EVM
+    pub fn reference(&self) -> &EvmTestHarness<DB, RefEvm> {
+        &self.reference
+    }
+
+    /// Get the harness running the reference EVM mutably
+    pub fn reference_mut(&mut self) -> &mut EvmTestHarness<DB, RefEvm> {
+        &mut self.reference
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit + CheatDb,
+        TestEvm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+        RefEvm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > DifferentialHarness<DB, TestEvm, RefEvm>
+{
+    /// Set the balance of an account on both EVMs
+    pub fn set_balance(&mut self, address: Address, balance: U256) -> Result<()> {
+        self.test.set_balance(address, balance)?;
+        self.reference.set_balance(address, balance)
+    }
+
+    /// Set the nonce of an account on both EVMs
+    pub fn set_nonce(&mut self, address: Address, nonce: u64) -> Result<()> {
+        self.test.set_nonce(address, nonce)?;
+        self.reference.set_nonce(address, nonce)
+    }
+
+    /// Set the runtime code of an account on both EVMs
+    pub fn set_code(&mut self, address: Address, code: impl Into<Bytes>) -> Result<()> {
+        let code = code.into();
+        self.test.set_code(address, code.clone())?;
+        self.reference.set_code(address, code)
+    }
+
+    /// Set a storage slot of an account on both EVMs
+    pub fn set_storage(&mut self, address: Address, slot: U256, value: U256) -> Result<()> {
+        self.test.set_storage(address, slot, value)?;
+        self.reference.set_storage(address, slot, value)
+    }
+
+    /// Remove an account on both EVMs
+    pub fn clear_account(&mut self, address: Address) -> Result<()> {
+        self.test.clear_account(address)?;
+        self.reference.clear_account(address)
+    }
+}
+
+/// Describe an executed transaction for a validity mismatch error
+fn outcome(result: &HarnessExecutionResult) -> String {
+    format!(
+        "executed (success={}, gas used={})",
+        result.success, result.gas_used
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{
+        create_dev_db, dev_account, dev_chain_spec,
+        test_utils::{CONTRACT, STORE_ONE},
+        TxBuilder, DEV_BALANCE,
+    };
+    use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::{database_interface::EmptyDB, db::CacheDB};
+
+    fn differential() -> DifferentialHarness<CacheDB<EmptyDB>, EthEvmFactory, EthEvmFactory> {
+        let mut harness = DifferentialHarness::new(
+            EthEvmFactory::default(),
+            EthEvmFactory::default(),
+            CacheDB::new(EmptyDB::default()),
+            dev_chain_spec(),
+        );
+        harness
+            .set_balance(dev_account(), U256::from(DEV_BALANCE))
+            .unwrap();
+        harness
+    }
+
+    fn call(nonce: u64, to: Address) -> TxEnv {
+        TxBuilder::eip1559()
+            .with_caller(dev_account())
+            .with_nonce(nonce)
+            .with_gas_limit(100_000)
+            .with_max_fee_per_gas(1_000_000_000)
+            .with_to(to)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_identical_evms_match() {
+        let mut harness = differential();
+        harness.set_code(CONTRACT, STORE_ONE.to_vec()).unwrap();
+
+        let comparisons = harness
+            .execute_txs([call(0, CONTRACT), call(1, CONTRACT)])
+            .unwrap();
+
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons.iter().all(EvmComparison::is_match));
+    }
+
+    #[test]
+    fn test_stops_at_first_divergence() {
+        let mut harness = differential().with_stop_at_first_divergence();
+        // Only the test EVM has code at the contract address
+        harness
+            .test_mut()
+            .set_code(CONTRACT, STORE_ONE.to_vec())
+            .unwrap();
+
+        let comparisons = harness
+            .execute_txs([call(0, CONTRACT), call(1, CONTRACT)])
+            .unwrap();
+
+        assert_eq!(comparisons.len(), 1);
+        assert!(!comparisons[0].is_match());
+    }
+
+    #[test]
+    fn test_validity_mismatch_runs_both_evms() {
+        let mut harness = DifferentialHarness::new(
+            EthEvmFactory::default(),
+            EthEvmFactory::default(),
+            CacheDB::new(EmptyDB::default()),
+            dev_chain_spec(),
+        );
+        // Only the reference EVM can pay for the transaction
+        harness
+            .reference_mut()
+            .set_balance(dev_account(), U256::from(DEV_BALANCE))
+            .unwrap();
+
+        let err = harness.execute_tx(call(0, CONTRACT)).unwrap_err();
+
+        assert!(err.to_string().contains("test EVM rejected"));
+        assert!(err.to_string().contains("reference=executed"));
+        assert_eq!(harness.reference_mut().get_nonce(dev_account()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_from_db_factory_with_dev_db() {
+        let mut harness = DifferentialHarness::from_db_factory(
+            EthEvmFactory::default(),
+            EthEvmFactory::default(),
+            create_dev_db,
+            dev_chain_spec(),
+        );
+        harness.set_code(CONTRACT, STORE_ONE.to_vec()).unwrap();
+
+        let comparison = harness.send_tx(call(0, CONTRACT)).unwrap();
+
+        assert!(comparison.is_match());
+    }
+}
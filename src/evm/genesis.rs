@@ -0,0 +1,262 @@
+//! Harnesses built from geth-style `genesis.json` files
+
+use super::{spec::blob_excess_gas_and_price, EvmTestHarness};
+use crate::{Error, Result};
+use alloy_genesis::{Genesis, GenesisAccount};
+use reth::revm::{
+    bytecode::Bytecode,
+    context::TxEnv,
+    context_interface::result::HaltReason,
+    database_interface::EmptyDB,
+    primitives::{hardfork::SpecId, Address, HashMap, KECCAK_EMPTY, U256},
+    state::AccountInfo,
+    State,
+};
+use reth_chainspec::ChainSpec;
+use reth_evm::EvmFactory;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// A genesis, or a path to a `genesis.json` file
+pub trait IntoGenesis {
+    /// Resolve into a genesis
+    fn into_genesis(self) -> Result<Genesis>;
+}
+
+impl IntoGenesis for Genesis {
+    fn into_genesis(self) -> Result<Genesis> {
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "fixtures")]
+impl IntoGenesis for &std::path::Path {
+    fn into_genesis(self) -> Result<Genesis> {
+        let content = std::fs::read_to_string(self).map_err(|e| Error::fixture(e.to_string()))?;
+        serde_json::from_str(&content)
+            .map_err(|e| Error::fixture(format!("Invalid genesis {}: {}", self.display(), e)))
+    }
+}
+
+#[cfg(feature = "fixtures")]
+impl IntoGenesis for std::path::PathBuf {
+    fn into_genesis(self) -> Result<Genesis> {
+        self.as_path().into_genesis()
+    }
+}
+
+#[cfg(feature = "fixtures")]
+impl IntoGenesis for &str {
+    fn into_genesis(self) -> Result<Genesis> {
+        std::path::Path::new(self).into_genesis()
+    }
+}
+
+/// Create a database holding the accounts of a genesis `alloc`
+///
+/// Fails if an account's code is not valid bytecode.
+pub fn create_genesis_db(alloc: &BTreeMap<Address, GenesisAccount>) -> Result<State<EmptyDB>> {
+    let mut db = State::builder().with_database(EmptyDB::default()).build();
+
+    for (address, account) in alloc {
+        let mut info = AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce.unwrap_or_default(),
+            code_hash: KECCAK_EMPTY,
+            code: None,
+        };
+        if let Some(code) = account.code.clone().filter(|code| !code.is_empty()) {
+            let bytecode = Bytecode::new_raw_checked(code)
+                .map_err(|e| Error::fixture(format!("Invalid code for {}: {}", address, e)))?;
+            info.code_hash = bytecode.hash_slow();
+            info.code = Some(bytecode);
+        }
+
+        let storage: HashMap<U256, U256> = account
+            .storage
+            .iter()
+            .flatten()
+            .map(|(slot, value)| ((*slot).into(), (*value).into()))
+            .collect();
+        db.insert_account_with_storage(*address, info, storage);
+    }
+
+    Ok(db)
+}
+
+impl<Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>> + Default>
+    EvmTestHarness<State<EmptyDB>, Evm>
+{
+    /// Create a harness from a genesis or a path to a `genesis.json` file
+    ///
+    /// The chain spec, including its fork schedule, comes from the genesis `config`, and
+    /// the database holds every `alloc` account with its code and storage. The block
+    /// environment starts at the chain spec's genesis header, so the base fee defaults
+    /// to the initial EIP-1559 base fee when London is active at genesis. Fails if the
+    /// base fee does not fit in a `u64`.
+    pub fn from_genesis(genesis: impl IntoGenesis) -> Result<Self> {
+        let genesis = genesis.into_genesis()?;
+        if genesis
+            .base_fee_per_gas
+            .is_some_and(|base_fee| u64::try_from(base_fee).is_err())
+        {
+            return Err(Error::fixture("Genesis base fee does not fit in a u64"));
+        }
+        let db = create_genesis_db(&genesis.alloc)?;
+        let chain_spec = Arc::new(ChainSpec::from(genesis));
+        let header = chain_spec.genesis_header().clone();
+
+        let mut harness = Self::new(Evm::default(), db, chain_spec);
+        let mut block_env = harness.block_env().clone();
+        block_env.number = U256::from(header.number);
+        block_env.timestamp = U256::from(header.timestamp);
+        block_env.gas_limit = header.gas_limit;
+        block_env.beneficiary = header.beneficiary;
+        block_env.difficulty = header.difficulty;
+        block_env.prevrandao = Some(header.mix_hash);
+        if let Some(base_fee) = header.base_fee_per_gas {
+            block_env.basefee = base_fee;
+        }
+        block_env.blob_excess_gas_and_price = blob_excess_gas_and_price(
+            harness.chain_spec(),
+            header.excess_blob_gas,
+            header.timestamp,
+        );
+        harness.set_block_env(block_env);
+
+        Ok(harness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::test_utils::{call_tx, CONTRACT, RETURN_SLOT_0};
+    use alloy_eips::eip1559::INITIAL_BASE_FEE;
+    use alloy_evm::eth::EthEvmFactory;
+    use alloy_genesis::ChainConfig;
+    use reth::revm::primitives::{Bytes, B256};
+
+    #[test]
+    fn test_from_genesis_seeds_alloc_and_forks() {
+        let sender = Address::with_last_byte(0xaa);
+        let config = ChainConfig {
+            chain_id: 1337,
+            homestead_block: Some(0),
+            eip150_block: Some(0),
+            eip155_block: Some(0),
+            eip158_block: Some(0),
+            byzantium_block: Some(0),
+            constantinople_block: Some(0),
+            petersburg_block: Some(0),
+            istanbul_block: Some(0),
+            berlin_block: Some(0),
+            london_block: Some(0),
+            terminal_total_difficulty: Some(U256::ZERO),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(0),
+            cancun_time: Some(100),
+            ..Default::default()
+        };
+        let code = Bytes::from_static(&RETURN_SLOT_0);
+        let genesis = Genesis {
+            config,
+            gas_limit: 30_000_000,
+            coinbase: Address::with_last_byte(0xbb),
+            mix_hash: B256::with_last_byte(0x11),
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        }
+        .extend_accounts([
+            (
+                sender,
+                GenesisAccount::default()
+                    .with_balance(U256::from(1_000_000_000_000_000_000u64))
+                    .with_nonce(Some(3)),
+            ),
+            (
+                CONTRACT,
+                GenesisAccount::default()
+                    .with_code(Some(code.clone()))
+                    .with_storage(Some(
+                        [(B256::ZERO, B256::with_last_byte(0x2a))]
+                            .into_iter()
+                            .collect(),
+                    )),
+            ),
+        ]);
+
+        let mut harness =
+            EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::from_genesis(genesis).unwrap();
+
+        assert_eq!(harness.chain_id(), 1337);
+        assert_eq!(harness.block_env().gas_limit, 30_000_000);
+        assert_eq!(
+            harness.block_env().beneficiary,
+            Address::with_last_byte(0xbb)
+        );
+        assert_eq!(
+            harness.block_env().prevrandao,
+            Some(B256::with_last_byte(0x11))
+        );
+        assert_eq!(harness.block_env().basefee, 7);
+        assert_eq!(harness.spec_id(), SpecId::SHANGHAI);
+        harness.set_timestamp(100);
+        assert_eq!(harness.spec_id(), SpecId::CANCUN);
+
+        assert_eq!(harness.get_nonce(sender).unwrap(), 3);
+        assert_eq!(harness.get_code(CONTRACT).unwrap(), code);
+        let tx = TxEnv {
+            caller: sender,
+            ..call_tx(CONTRACT)
+        };
+        let result = harness.call(tx).unwrap();
+        assert_eq!(U256::from_be_slice(&result.output), U256::from(0x2a));
+    }
+
+    #[test]
+    fn test_from_genesis_starts_at_genesis_header() {
+        let config = ChainConfig {
+            chain_id: 1337,
+            homestead_block: Some(0),
+            eip150_block: Some(0),
+            eip155_block: Some(0),
+            eip158_block: Some(0),
+            byzantium_block: Some(0),
+            constantinople_block: Some(0),
+            petersburg_block: Some(0),
+            istanbul_block: Some(0),
+            berlin_block: Some(0),
+            london_block: Some(0),
+            terminal_total_difficulty: Some(U256::ZERO),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(0),
+            cancun_time: Some(0),
+            ..Default::default()
+        };
+        let genesis = Genesis {
+            config,
+            number: Some(5),
+            excess_blob_gas: Some(10_000_000),
+            ..Default::default()
+        };
+
+        let harness =
+            EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::from_genesis(genesis).unwrap();
+
+        assert_eq!(harness.block_number(), 5);
+        assert_eq!(harness.block_env().basefee, INITIAL_BASE_FEE);
+        let blob_price = harness.block_env().blob_excess_gas_and_price.unwrap();
+        assert_eq!(blob_price.excess_blob_gas, 10_000_000);
+        assert!(blob_price.blob_gasprice > 1);
+    }
+
+    #[test]
+    fn test_from_genesis_rejects_oversized_base_fee() {
+        let genesis = Genesis {
+            base_fee_per_gas: Some(u128::from(u64::MAX) + 1),
+            ..Default::default()
+        };
+
+        assert!(EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::from_genesis(genesis).is_err());
+    }
+}
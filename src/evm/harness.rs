@@ -1,16 +1,31 @@
 //! Core EVM test harness for in-memory execution
 
-use super::result::HarnessExecutionResult;
-use crate::{Error, Result};
+use super::{
+    dev::dev_chain_spec,
+    diff::StateDiff,
+    result::{HarnessExecutionResult, SimulationOptions, SimulationResult},
+    snapshot::{HarnessSnapshot, SnapshotDb, SnapshotId},
+    spec::spec_id_at,
+    tx::{TxBuilder, EIP1559_TX_TYPE, EIP4844_TX_TYPE, EIP7702_TX_TYPE},
+};
+use crate::{fixtures::GasSnapshots, Error, Result};
 use reth::revm::{
     context::{BlockEnv, CfgEnv, TxEnv},
+    context_interface::result::{HaltReason, ResultAndState},
     database_interface::{DatabaseCommit, EmptyDB},
-    primitives::{hardfork::SpecId, Address, Bytes, TxKind, U256},
-    State,
+    primitives::{hardfork::SpecId, Address, Bytes, B256, KECCAK_EMPTY, U256},
+    state::{AccountInfo, EvmState},
+    Inspector, State,
 };
 use reth_chainspec::{ChainSpec, EthChainSpec};
 use reth_evm::{Database, Evm, EvmEnv, EvmFactory};
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
+
+/// Priority fee used when filling transactions (1 gwei)
+const DEFAULT_PRIORITY_FEE: u128 = 1_000_000_000;
+
+/// Upper bound for gas estimation
+const ESTIMATE_GAS_CAP: u64 = 30_000_000;
 
 /// In-memory EVM test harness
 ///
@@ -26,9 +41,19 @@ pub struct EvmTestHarness<DB: Database, Evm: EvmFactory> {
     block_env: BlockEnv,
     /// EVM configuration
     cfg_env: CfgEnv,
+    /// Spec ID to use instead of the one derived from the chain spec
+    spec_override: Option<SpecId>,
+    /// Snapshots taken with `snapshot`, oldest first
+    snapshots: Vec<(SnapshotId, Box<dyn Any + Send + Sync>)>,
+    /// ID assigned to the next snapshot
+    next_snapshot_id: u64,
+    /// Gas snapshots recorded with `gas_snapshot`
+    gas_snapshots: Option<GasSnapshots>,
 }
 
-impl<Evm: EvmFactory<Spec = SpecId, Tx = TxEnv> + Default> EvmTestHarness<State<EmptyDB>, Evm> {
+impl<Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>> + Default>
+    EvmTestHarness<State<EmptyDB>, Evm>
+{
     /// Create a new builder with an empty State database
     pub fn builder() -> EvmTestHarnessBuilder<State<EmptyDB>, Evm> {
         let db = State::builder().with_database(EmptyDB::default()).build();
@@ -39,17 +64,21 @@ impl<Evm: EvmFactory<Spec = SpecId, Tx = TxEnv> + Default> EvmTestHarness<State<
             block_number: 0,
             timestamp: 0,
             base_fee: None,
-            spec_id: SpecId::CANCUN,
+            spec_id: None,
         }
     }
 }
 
-impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>> EvmTestHarness<DB, Evm> {
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
     /// Create a new EVM test harness
     pub fn new(evm_factory: Evm, db: DB, chain_spec: Arc<ChainSpec>) -> Self {
         let mut cfg_env = CfgEnv::default();
         cfg_env.chain_id = chain_spec.chain().id();
-        cfg_env.spec = SpecId::CANCUN;
+        cfg_env.spec = spec_id_at(&chain_spec, 0, 0);
 
         Self {
             evm_factory,
@@ -57,118 +86,273 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
             chain_spec,
             block_env: BlockEnv::default(),
             cfg_env,
+            spec_override: None,
+            snapshots: Vec::new(),
+            next_snapshot_id: 0,
+            gas_snapshots: None,
         }
     }
 
-    /// Execute a transaction
+    /// Execute a transaction and commit its state changes
     pub fn execute_tx(&mut self, tx: TxEnv) -> Result<HarnessExecutionResult> {
-        let env = EvmEnv {
-            block_env: self.block_env.clone(),
-            cfg_env: self.cfg_env.clone(),
-        };
+        let ResultAndState { result, state } = self.transact(tx, self.evm_env())?;
+        let state_diff = self.state_diff(&state)?;
+
+        // Commit state changes to the database
+        self.db.commit(state);
+
+        Ok(HarnessExecutionResult::from(result).with_state_diff(state_diff))
+    }
+
+    /// Execute a transaction with an inspector attached and commit its state changes
+    ///
+    /// The inspector is returned alongside the result so it can be queried afterwards.
+    pub fn execute_tx_with_inspector<I>(
+        &mut self,
+        tx: TxEnv,
+        mut inspector: I,
+    ) -> Result<(HarnessExecutionResult, I)>
+    where
+        I: for<'a> Inspector<Evm::Context<&'a mut DB>>,
+    {
+        let ResultAndState { result, state } = self.inspect(tx, self.evm_env(), &mut inspector)?;
+        let state_diff = self.state_diff(&state)?;
+
+        self.db.commit(state);
+
+        let result = HarnessExecutionResult::from(result).with_state_diff(state_diff);
+        Ok((result, inspector))
+    }
+
+    /// Execute a transaction against the current state without committing it
+    ///
+    /// The returned state holds the changes the transaction would have made.
+    pub fn simulate_tx(&mut self, tx: TxEnv) -> Result<SimulationResult> {
+        self.simulate_tx_with(tx, SimulationOptions::default())
+    }
+
+    /// Execute a transaction without committing it, relaxing validation as configured
+    pub fn simulate_tx_with(
+        &mut self,
+        tx: TxEnv,
+        options: SimulationOptions,
+    ) -> Result<SimulationResult> {
+        let mut env = self.evm_env();
+        env.cfg_env.disable_balance_check = options.disable_balance_check;
+        env.cfg_env.disable_nonce_check = options.disable_nonce_check;
+        env.cfg_env.disable_base_fee = options.disable_base_fee;
+
+        let ResultAndState { result, state } = self.transact(tx, env)?;
+        let state_diff = self.state_diff(&state)?;
+
+        Ok(SimulationResult {
+            result: HarnessExecutionResult::from(result).with_state_diff(state_diff),
+            state,
+        })
+    }
 
-        let result_and_state = {
-            let mut evm = self.evm_factory.create_evm(&mut self.db, env);
-            evm.transact(tx)
+    /// Execute a call with `eth_call` semantics
+    ///
+    /// Balance, nonce and base fee checks are disabled and no state is committed.
+    pub fn call(&mut self, tx: TxEnv) -> Result<HarnessExecutionResult> {
+        self.simulate_tx_with(tx, SimulationOptions::eth_call())
+            .map(|simulation| simulation.result)
+    }
+
+    /// Fill in the sender nonce, chain ID and gas price, then execute the transaction
+    ///
+    /// See [`Self::fill_tx`] for how each field is filled.
+    pub fn send_tx(&mut self, tx: TxEnv) -> Result<HarnessExecutionResult> {
+        let tx = self.fill_tx(tx)?;
+        self.execute_tx(tx)
+    }
+
+    /// Like [`Self::send_tx`], but also replaces the gas limit with an estimate
+    pub fn send_tx_with_gas_estimate(&mut self, tx: TxEnv) -> Result<HarnessExecutionResult> {
+        let mut tx = self.fill_tx(tx)?;
+        tx.gas_limit = self.estimate_gas(tx.clone())?;
+        self.execute_tx(tx)
+    }
+
+    /// Fill in the fields tests usually have to track by hand
+    ///
+    /// The nonce is read from the database and the chain ID from the EVM configuration.
+    /// A zero gas price is replaced with twice the current base fee plus a 1 gwei tip,
+    /// which is also used as the priority fee of EIP-1559 style transactions without one.
+    /// Unknown transaction types, such as custom deposit types, get no priority fee.
+    pub fn fill_tx(&mut self, mut tx: TxEnv) -> Result<TxEnv> {
+        tx.nonce = self.get_nonce(tx.caller)?;
+        tx.chain_id = Some(self.cfg_env.chain_id);
+
+        if tx.gas_price == 0 {
+            tx.gas_price = 2 * self.block_env.basefee as u128 + DEFAULT_PRIORITY_FEE;
+        }
+        let dynamic_fee = matches!(
+            tx.tx_type,
+            EIP1559_TX_TYPE | EIP4844_TX_TYPE | EIP7702_TX_TYPE
+        );
+        if dynamic_fee && tx.gas_priority_fee.is_none() {
+            tx.gas_priority_fee = Some(DEFAULT_PRIORITY_FEE.min(tx.gas_price));
+        }
+
+        Ok(tx)
+    }
+
+    /// Estimate the lowest gas limit at which the transaction succeeds
+    ///
+    /// Binary searches between the gas used and the block gas limit (capped at
+    /// 30M gas) without committing any state. The sender balance is not checked.
+    pub fn estimate_gas(&mut self, mut tx: TxEnv) -> Result<u64> {
+        let options = SimulationOptions {
+            disable_balance_check: true,
+            ..Default::default()
         };
+        let cap = self.block_env.gas_limit.min(ESTIMATE_GAS_CAP);
+
+        tx.gas_limit = cap;
+        let result = self.simulate_tx_with(tx.clone(), options)?.result;
+        if !result.is_success() {
+            return Err(Error::evm_execution(format!(
+                "Gas estimation failed at gas limit {}: {}",
+                cap,
+                result
+                    .revert_reason()
+                    .map_or_else(|| "unknown reason".to_string(), ToString::to_string)
+            )));
+        }
 
-        match result_and_state {
-            Ok(result_and_state) => {
-                let result = result_and_state.result;
-                let state = result_and_state.state;
-
-                // Commit state changes to the database
-                self.db.commit(state);
-
-                match result {
-                    reth::revm::context_interface::result::ExecutionResult::Success {
-                        output,
-                        gas_used,
-                        gas_refunded,
-                        ..
-                    } => {
-                        Ok(HarnessExecutionResult {
-                            success: true,
-                            gas_used,
-                            gas_refunded,
-                            output: output.into_data(),
-                            logs: Vec::new(), // TODO: Extract logs from state
-                            revert_reason: None,
-                        })
-                    }
-                    reth::revm::context_interface::result::ExecutionResult::Revert {
-                        output,
-                        gas_used,
-                    } => {
-                        Ok(HarnessExecutionResult {
-                            success: false,
-                            gas_used,
-                            gas_refunded: 0,
-                            output,
-                            logs: Vec::new(),
-                            revert_reason: Some("Transaction reverted".to_string()),
-                        })
-                    }
-                    reth::revm::context_interface::result::ExecutionResult::Halt {
-                        reason,
-                        gas_used,
-                    } => {
-                        Ok(HarnessExecutionResult {
-                            success: false,
-                            gas_used,
-                            gas_refunded: 0,
-                            output: Bytes::new(),
-                            logs: Vec::new(),
-                            revert_reason: Some(format!("Transaction halted: {:?}", reason)),
-                        })
-                    }
-                }
+        // Refunds and the 63/64 rule mean the gas used is only a lower bound
+        let mut lowest_failing = result.gas_used.saturating_sub(1);
+        let mut highest_succeeding = cap;
+        while lowest_failing + 1 < highest_succeeding {
+            let mid = lowest_failing + (highest_succeeding - lowest_failing) / 2;
+            tx.gas_limit = mid;
+
+            let succeeded = self
+                .simulate_tx_with(tx.clone(), options)
+                .is_ok_and(|simulation| simulation.result.is_success());
+            if succeeded {
+                highest_succeeding = mid;
+            } else {
+                lowest_failing = mid;
             }
-            Err(e) => Err(Error::evm_execution(format!(
-                "EVM execution failed: {:?}",
-                e
-            ))),
+        }
+
+        Ok(highest_succeeding)
+    }
+
+    /// Run a transaction through the EVM without touching committed state
+    fn transact(&mut self, tx: TxEnv, env: EvmEnv) -> Result<ResultAndState<Evm::HaltReason>> {
+        let mut evm = self.evm_factory.create_evm(&mut self.db, env);
+        evm.transact(tx)
+            .map_err(|e| Error::evm_execution(format!("EVM execution failed: {:?}", e)))
+    }
+
+    /// Like [`Self::transact`], with an inspector attached
+    fn inspect<I>(
+        &mut self,
+        tx: TxEnv,
+        env: EvmEnv,
+        inspector: I,
+    ) -> Result<ResultAndState<Evm::HaltReason>>
+    where
+        I: for<'a> Inspector<Evm::Context<&'a mut DB>>,
+    {
+        let mut evm = self
+            .evm_factory
+            .create_evm_with_inspector(&mut self.db, env, inspector);
+        evm.transact(tx)
+            .map_err(|e| Error::evm_execution(format!("EVM execution failed: {:?}", e)))
+    }
+
+    /// Diff uncommitted EVM state against the database
+    fn state_diff(&mut self, state: &EvmState) -> Result<StateDiff> {
+        StateDiff::compute(&mut self.db, state)
+            .map_err(|e| Error::evm_execution(format!("Failed to load account: {:?}", e)))
+    }
+
+    /// Commit account changes produced outside of `execute_tx`
+    ///
+    /// Accounts are loaded first, since `State` only accepts changes to cached accounts.
+    pub(crate) fn commit_accounts(&mut self, state: EvmState) -> Result<()> {
+        for address in state.keys() {
+            self.db
+                .basic(*address)
+                .map_err(|e| Error::evm_execution(format!("Failed to load account: {:?}", e)))?;
+        }
+
+        self.db.commit(state);
+
+        Ok(())
+    }
+
+    /// Build the EVM environment from the current block and config
+    fn evm_env(&self) -> EvmEnv {
+        EvmEnv {
+            block_env: self.block_env.clone(),
+            cfg_env: self.cfg_env.clone(),
         }
     }
 
     /// Execute a precompile call
     ///
     /// Uses `caller` as the transaction sender. If None, uses Address::ZERO
-    /// which may not have funds (use a funded account if needed).
+    /// which may not have funds (use a funded account if needed). The transaction is
+    /// filled like [`Self::send_tx`], so repeated calls from one sender work.
     pub fn execute_precompile(
         &mut self,
         address: Address, // TODO: add eth and other precompile libs
         input: Bytes,
         caller: Option<Address>,
     ) -> Result<HarnessExecutionResult> {
-        let tx = TxEnv {
-            caller: caller.unwrap_or(Address::ZERO),
-            gas_limit: 10_000_000,
-            gas_price: 1_000_000_000u128, // 1 gwei
-            kind: TxKind::Call(address),
-            value: U256::ZERO,
-            data: input,
-            nonce: 0,
-            chain_id: Some(self.chain_spec.chain().id()),
-            access_list: Default::default(),
-            gas_priority_fee: Default::default(),
-            blob_hashes: vec![],
-            max_fee_per_blob_gas: 0,
-            authorization_list: vec![],
-            tx_type: 0,
-        };
-
-        self.execute_tx(tx)
+        let tx = TxBuilder::legacy()
+            .with_caller(caller.unwrap_or(Address::ZERO))
+            .with_gas_limit(10_000_000)
+            .with_to(address)
+            .with_input(input)
+            .build()?;
+
+        self.send_tx(tx)
     }
 
-    /// Set the block number
+    /// Set the block number, updating the active spec
     pub fn set_block_number(&mut self, number: u64) {
         self.block_env.number = U256::from(number);
+        self.update_spec_id();
     }
 
-    /// Set the block timestamp
+    /// Set the block timestamp, updating the active spec
     pub fn set_timestamp(&mut self, timestamp: u64) {
         self.block_env.timestamp = U256::from(timestamp);
+        self.update_spec_id();
+    }
+
+    /// Pin the spec ID instead of deriving it from the chain spec
+    pub fn set_spec_id(&mut self, spec_id: SpecId) {
+        self.spec_override = Some(spec_id);
+        self.update_spec_id();
+    }
+
+    /// Remove a pinned spec ID and derive it from the chain spec again
+    pub fn clear_spec_id(&mut self) {
+        self.spec_override = None;
+        self.update_spec_id();
+    }
+
+    /// Get the active spec ID
+    pub fn spec_id(&self) -> SpecId {
+        self.cfg_env.spec
+    }
+
+    /// Resolve the active spec from the override or the chain's hardfork schedule
+    fn update_spec_id(&mut self) {
+        self.cfg_env.spec = self.spec_override.unwrap_or_else(|| {
+            spec_id_at(
+                &self.chain_spec,
+                self.block_env.number.saturating_to(),
+                self.block_env.timestamp.saturating_to(),
+            )
+        });
     }
 
     /// Set the block base fee
@@ -186,6 +370,50 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
         self.cfg_env.chain_id
     }
 
+    /// Get the chain spec
+    pub fn chain_spec(&self) -> &Arc<ChainSpec> {
+        &self.chain_spec
+    }
+
+    /// Get the current block environment
+    pub fn block_env(&self) -> &BlockEnv {
+        &self.block_env
+    }
+
+    /// Replace the block environment, updating the active spec
+    pub fn set_block_env(&mut self, block_env: BlockEnv) {
+        self.block_env = block_env;
+        self.update_spec_id();
+    }
+
+    /// Enable gas snapshots for `gas_snapshot`
+    pub fn set_gas_snapshots(&mut self, gas_snapshots: GasSnapshots) {
+        self.gas_snapshots = Some(gas_snapshots);
+    }
+
+    /// Get the gas snapshots, for example to report mismatches collected in warn-only mode
+    pub fn gas_snapshots(&self) -> Option<&GasSnapshots> {
+        self.gas_snapshots.as_ref()
+    }
+
+    /// Record the gas used by a result under `name`, comparing it to the committed value
+    ///
+    /// Fails if gas snapshots are not enabled or, in check mode, if the gas drifted
+    /// beyond the configured tolerance.
+    pub fn gas_snapshot(&mut self, name: &str, result: &HarnessExecutionResult) -> Result<()> {
+        self.gas_snapshots
+            .as_mut()
+            .ok_or_else(|| Error::fixture("Gas snapshots are not enabled"))?
+            .record(name, result.gas_used)
+    }
+
+    /// Write recorded gas snapshots to their file when in update mode
+    pub fn write_gas_snapshots(&self) -> Result<()> {
+        self.gas_snapshots
+            .as_ref()
+            .map_or(Ok(()), GasSnapshots::write)
+    }
+
     /// Get a reference to the database
     pub fn db(&self) -> &DB {
         &self.db
@@ -197,6 +425,56 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
     }
 }
 
+impl<DB: Database + DatabaseCommit + SnapshotDb, Evm: EvmFactory> EvmTestHarness<DB, Evm> {
+    /// Take a snapshot of the database, block environment, EVM configuration and pinned spec
+    ///
+    /// Mirrors anvil's `evm_snapshot`.
+    pub fn snapshot(&mut self) -> SnapshotId {
+        let id = SnapshotId(self.next_snapshot_id);
+        self.next_snapshot_id += 1;
+
+        let snapshot = HarnessSnapshot {
+            db: self.db.snapshot(),
+            block_env: self.block_env.clone(),
+            cfg_env: self.cfg_env.clone(),
+            spec_override: self.spec_override,
+        };
+        self.snapshots.push((id, Box::new(snapshot)));
+
+        id
+    }
+
+    /// Revert to a snapshot, discarding it and every snapshot taken after it
+    ///
+    /// Returns `false` if the snapshot does not exist. Mirrors anvil's `evm_revert`.
+    pub fn revert_to(&mut self, id: SnapshotId) -> bool {
+        let Some(position) = self
+            .snapshots
+            .iter()
+            .position(|(snapshot_id, _)| *snapshot_id == id)
+        else {
+            return false;
+        };
+
+        // Snapshots are only pushed by `snapshot`, so they always hold this database's type
+        let snapshot = self
+            .snapshots
+            .drain(position..)
+            .next()
+            .map(|(_, snapshot)| snapshot.downcast::<HarnessSnapshot<DB::Snapshot>>());
+        let Some(Ok(snapshot)) = snapshot else {
+            return false;
+        };
+
+        self.db.restore(snapshot.db);
+        self.block_env = snapshot.block_env;
+        self.cfg_env = snapshot.cfg_env;
+        self.spec_override = snapshot.spec_override;
+
+        true
+    }
+}
+
 /// Builder for `EvmTestHarness`
 pub struct EvmTestHarnessBuilder<DB: Database + DatabaseCommit, Evm: EvmFactory> {
     evm_factory: Option<Evm>,
@@ -205,11 +483,13 @@ pub struct EvmTestHarnessBuilder<DB: Database + DatabaseCommit, Evm: EvmFactory>
     block_number: u64,
     timestamp: u64,
     base_fee: Option<u64>,
-    spec_id: SpecId,
+    spec_id: Option<SpecId>,
 }
 
-impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
-    EvmTestHarnessBuilder<DB, Evm>
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarnessBuilder<DB, Evm>
 {
     /// Create a new builder (requires explicit database)
     pub fn with_db(db: DB) -> Self {
@@ -220,7 +500,7 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
             block_number: 0,
             timestamp: 0,
             base_fee: None,
-            spec_id: SpecId::CANCUN,
+            spec_id: None,
         }
     }
 
@@ -254,9 +534,9 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
         self
     }
 
-    /// Set the spec ID
+    /// Pin the spec ID instead of deriving it from the chain spec
     pub fn with_spec_id(mut self, spec_id: SpecId) -> Self {
-        self.spec_id = spec_id;
+        self.spec_id = Some(spec_id);
         self
     }
 
@@ -267,9 +547,7 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
     {
         let evm_factory = self.evm_factory.unwrap_or_default();
         let db = self.db.expect("Database must be provided");
-        let chain_spec = self
-            .chain_spec
-            .unwrap_or_else(|| Arc::new(ChainSpec::default()));
+        let chain_spec = self.chain_spec.unwrap_or_else(dev_chain_spec);
 
         let mut harness = EvmTestHarness::new(evm_factory, db, chain_spec);
         harness.set_block_number(self.block_number);
@@ -277,33 +555,88 @@ impl<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>
         if let Some(base_fee) = self.base_fee {
             harness.set_base_fee(base_fee);
         }
-        harness.cfg_env.spec = self.spec_id;
+        if let Some(spec_id) = self.spec_id {
+            harness.set_spec_id(spec_id);
+        }
 
         harness
     }
 }
 
-// Specialized implementation for State databases
-impl<DB: Database, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>> EvmTestHarness<State<DB>, Evm> {
+/// Account reads, available for any `Database` (wrap `DatabaseRef` backends in `WrapDatabaseRef`)
+impl<DB: Database, Evm: EvmFactory> EvmTestHarness<DB, Evm> {
+    /// Get an account with its code loaded, or `None` if it does not exist
+    pub fn get_account(&mut self, address: Address) -> Result<Option<AccountInfo>> {
+        let Some(mut info) = self
+            .db
+            .basic(address)
+            .map_err(|e| Error::evm_execution(format!("Failed to load account: {:?}", e)))?
+        else {
+            return Ok(None);
+        };
+
+        if info.code.is_none() && info.code_hash != KECCAK_EMPTY {
+            let code = self
+                .db
+                .code_by_hash(info.code_hash)
+                .map_err(|e| Error::evm_execution(format!("Failed to load code: {:?}", e)))?;
+            info.code = Some(code);
+        }
+
+        Ok(Some(info))
+    }
+
     /// Get the balance of an account
     pub fn get_balance(&mut self, address: Address) -> Result<U256> {
-        match self.db.load_cache_account(address) {
-            Ok(account) => match account.account_info() {
-                Some(info) => Ok(info.balance),
-                None => Ok(U256::ZERO),
-            },
-            Err(e) => Err(Error::evm_execution(format!(
-                "Failed to load account: {:?}",
-                e
-            ))),
-        }
+        Ok(self
+            .get_account(address)?
+            .map_or(U256::ZERO, |info| info.balance))
+    }
+
+    /// Get the nonce of an account
+    pub fn get_nonce(&mut self, address: Address) -> Result<u64> {
+        Ok(self.get_account(address)?.map_or(0, |info| info.nonce))
+    }
+
+    /// Get the runtime code of an account, empty if it has none
+    pub fn get_code(&mut self, address: Address) -> Result<Bytes> {
+        Ok(self
+            .get_account(address)?
+            .and_then(|info| info.code)
+            .map(|code| code.original_bytes())
+            .unwrap_or_default())
+    }
+
+    /// Get the code hash of an account, zero if the account does not exist (as `EXTCODEHASH`)
+    pub fn get_code_hash(&mut self, address: Address) -> Result<B256> {
+        Ok(self
+            .get_account(address)?
+            .map_or(B256::ZERO, |info| info.code_hash))
+    }
+
+    /// Get the value of a storage slot
+    pub fn get_storage(&mut self, address: Address, slot: U256) -> Result<U256> {
+        self.db
+            .storage(address, slot)
+            .map_err(|e| Error::evm_execution(format!("Failed to load storage: {:?}", e)))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::evm::{
+        create_dev_db, dev_account,
+        test_utils::{call_tx, dev_harness_with_code, CONTRACT, RETURN_EMPTY},
+        Change, DevHarness,
+    };
     use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::{
+        db::CacheDB,
+        interpreter::Interpreter,
+        primitives::{keccak256, TxKind},
+    };
+    use reth_chainspec::{ChainSpecBuilder, EthereumHardfork, ForkCondition};
 
     #[test]
     fn test_harness_creation() {
@@ -325,4 +658,245 @@ mod tests {
         assert_eq!(harness.block_env.timestamp, U256::from(1234567890));
         assert_eq!(harness.block_env.basefee, 1_000_000_000);
     }
+
+    #[test]
+    fn test_spec_id_follows_chain_spec() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .cancun_activated()
+            .with_fork(EthereumHardfork::Prague, ForkCondition::Timestamp(1_000))
+            .build();
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::builder()
+            .with_chain_spec(Arc::new(chain_spec))
+            .build();
+        assert_eq!(harness.spec_id(), SpecId::CANCUN);
+
+        harness.set_timestamp(1_000);
+        assert_eq!(harness.spec_id(), SpecId::PRAGUE);
+
+        harness.set_spec_id(SpecId::SHANGHAI);
+        harness.set_timestamp(2_000);
+        assert_eq!(harness.spec_id(), SpecId::SHANGHAI);
+
+        harness.clear_spec_id();
+        assert_eq!(harness.spec_id(), SpecId::PRAGUE);
+    }
+
+    #[test]
+    fn test_account_reads_on_cache_db() {
+        let mut harness = EvmTestHarness::new(
+            EthEvmFactory::default(),
+            CacheDB::new(EmptyDB::default()),
+            dev_chain_spec(),
+        );
+        let contract = Address::with_last_byte(0xcc);
+        let code = Bytes::from_static(&[0x60, 0x00, 0x00]);
+
+        harness.set_code(contract, code.clone()).unwrap();
+        harness
+            .set_storage(contract, U256::from(1), U256::from(2))
+            .unwrap();
+
+        assert_eq!(harness.get_code(contract).unwrap(), code);
+        assert_eq!(harness.get_code_hash(contract).unwrap(), keccak256(&code));
+        assert_eq!(
+            harness.get_storage(contract, U256::from(1)).unwrap(),
+            U256::from(2)
+        );
+        assert_eq!(harness.get_code_hash(dev_account()).unwrap(), B256::ZERO);
+        assert!(harness.get_account(dev_account()).unwrap().is_none());
+    }
+
+    #[derive(Default)]
+    struct StepCounter {
+        steps: usize,
+    }
+
+    impl<CTX> Inspector<CTX> for StepCounter {
+        fn step(&mut self, _interp: &mut Interpreter, _context: &mut CTX) {
+            self.steps += 1;
+        }
+    }
+
+    #[test]
+    fn test_repeated_precompile_calls_from_one_sender() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let identity = Address::with_last_byte(4);
+        let input = Bytes::from_static(&[1, 2, 3]);
+
+        for _ in 0..2 {
+            let result = harness
+                .execute_precompile(identity, input.clone(), Some(dev_account()))
+                .unwrap();
+            assert_eq!(result.output, input);
+        }
+
+        assert_eq!(harness.get_nonce(dev_account()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_execute_tx_with_inspector() {
+        let mut harness = dev_harness_with_code(&RETURN_EMPTY);
+        let tx = harness.fill_tx(call_tx(CONTRACT)).unwrap();
+
+        let (result, inspector) = harness
+            .execute_tx_with_inspector(tx, StepCounter::default())
+            .unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(inspector.steps, 3);
+        assert_eq!(harness.get_nonce(dev_account()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_simulate_tx_does_not_commit() {
+        let mut harness =
+            EvmTestHarness::new(EthEvmFactory::default(), create_dev_db(), dev_chain_spec());
+        let sender = dev_account();
+        let balance_before = harness.get_balance(sender).unwrap();
+        let tx = TxEnv {
+            caller: sender,
+            gas_limit: 21_000,
+            gas_price: 1_000_000_000u128,
+            kind: TxKind::Call(Address::with_last_byte(0xaa)),
+            value: U256::from(1),
+            chain_id: Some(harness.chain_id()),
+            ..Default::default()
+        };
+
+        let simulation = harness.simulate_tx(tx.clone()).unwrap();
+        assert!(simulation.result.is_success());
+        assert!(simulation.state.contains_key(&sender));
+        let state_diff = &simulation.result.state_diff;
+        let sender_diff = state_diff.account(sender).unwrap();
+        assert_eq!(sender_diff.nonce, Change::new(0, 1));
+        let receiver_diff = state_diff.account(Address::with_last_byte(0xaa)).unwrap();
+        assert_eq!(
+            receiver_diff.balance,
+            Change::new(U256::ZERO, U256::from(1))
+        );
+        assert_eq!(harness.get_balance(sender).unwrap(), balance_before);
+
+        // The nonce was not bumped, so the same transaction still executes
+        assert!(harness.execute_tx(tx).unwrap().is_success());
+    }
+
+    #[test]
+    fn test_call_skips_balance_and_nonce_checks() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::builder().build();
+        let tx = TxEnv {
+            caller: Address::with_last_byte(0xbb),
+            nonce: 7,
+            gas_limit: 100_000,
+            gas_price: 1_000_000_000u128,
+            kind: TxKind::Call(Address::with_last_byte(4)),
+            data: Bytes::from_static(&[1, 2, 3]),
+            chain_id: Some(harness.chain_id()),
+            ..Default::default()
+        };
+
+        let result = harness.call(tx.clone()).unwrap();
+        assert_eq!(result.output, Bytes::from_static(&[1, 2, 3]));
+
+        assert!(harness.execute_tx(tx).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_and_revert() {
+        let mut harness =
+            EvmTestHarness::new(EthEvmFactory::default(), create_dev_db(), dev_chain_spec());
+        let sender = dev_account();
+        let balance_before = harness.get_balance(sender).unwrap();
+
+        let snapshot = harness.snapshot();
+        harness.set_block_number(10);
+        let tx = TxEnv {
+            caller: sender,
+            gas_limit: 21_000,
+            gas_price: 1_000_000_000u128,
+            kind: TxKind::Call(Address::with_last_byte(0xaa)),
+            value: U256::from(1),
+            chain_id: Some(harness.chain_id()),
+            ..Default::default()
+        };
+        assert!(harness.execute_tx(tx).unwrap().is_success());
+        assert_ne!(harness.get_balance(sender).unwrap(), balance_before);
+
+        assert!(harness.revert_to(snapshot));
+        assert_eq!(harness.get_balance(sender).unwrap(), balance_before);
+        assert_eq!(harness.block_number(), 0);
+
+        // Reverting consumes the snapshot
+        assert!(!harness.revert_to(snapshot));
+
+        // Stored snapshots must not stop harnesses from moving across threads
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<EvmTestHarness<State<EmptyDB>, EthEvmFactory>>();
+    }
+
+    #[test]
+    fn test_revert_restores_spec_override() {
+        let mut harness =
+            EvmTestHarness::new(EthEvmFactory::default(), create_dev_db(), dev_chain_spec());
+
+        let snapshot = harness.snapshot();
+        harness.set_spec_id(SpecId::SHANGHAI);
+        assert!(harness.revert_to(snapshot));
+
+        // The pin must not come back once the spec is derived again
+        harness.set_block_number(1);
+        assert_eq!(harness.spec_id(), SpecId::CANCUN);
+    }
+
+    #[test]
+    fn test_send_tx_fills_nonce() {
+        let mut harness =
+            EvmTestHarness::new(EthEvmFactory::default(), create_dev_db(), dev_chain_spec());
+        harness.set_base_fee(1_000_000_000);
+        let tx = TxEnv {
+            caller: dev_account(),
+            gas_limit: 21_000,
+            kind: TxKind::Call(Address::with_last_byte(0xaa)),
+            value: U256::from(1),
+            tx_type: 2,
+            ..Default::default()
+        };
+
+        assert!(harness.send_tx(tx.clone()).unwrap().is_success());
+        assert!(harness.send_tx(tx).unwrap().is_success());
+        assert_eq!(harness.get_nonce(dev_account()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_fill_tx_leaves_custom_types_without_priority_fee() {
+        let mut harness =
+            EvmTestHarness::new(EthEvmFactory::default(), create_dev_db(), dev_chain_spec());
+        let tx = |tx_type| TxEnv {
+            caller: dev_account(),
+            tx_type,
+            ..Default::default()
+        };
+
+        let filled = harness.fill_tx(tx(EIP1559_TX_TYPE)).unwrap();
+        assert!(filled.gas_priority_fee.is_some());
+
+        // Deposit transactions carry no priority fee
+        let filled = harness.fill_tx(tx(0x7e)).unwrap();
+        assert_eq!(filled.gas_priority_fee, None);
+    }
+
+    #[test]
+    fn test_estimate_gas_for_transfer() {
+        let mut harness =
+            EvmTestHarness::new(EthEvmFactory::default(), create_dev_db(), dev_chain_spec());
+        let tx = harness
+            .fill_tx(TxEnv {
+                caller: dev_account(),
+                kind: TxKind::Call(Address::with_last_byte(0xaa)),
+                value: U256::from(1),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(harness.estimate_gas(tx).unwrap(), 21_000);
+    }
 }
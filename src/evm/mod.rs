@@ -1,11 +1,49 @@
 //! EVM testing utilities for in-memory execution
 
+mod abi;
+mod block;
+mod call_trace;
+mod cheats;
+mod create;
 mod dev;
+mod diff;
+mod difference;
+mod differential;
+mod genesis;
 mod harness;
+mod profile;
+mod raw;
 mod result;
+mod revert;
+mod snapshot;
+mod spec;
+#[cfg(feature = "fixtures")]
+mod state_dump;
+#[cfg(test)]
+pub(crate) mod test_utils;
+mod trace;
+mod tx;
 
+pub use alloy_rpc_types_trace::geth::{CallFrame, CallLogFrame};
+pub use cheats::CheatDb;
+pub use create::{create2_address, create_address};
 pub use dev::{
-    create_dev_db, dev_account, dev_account_at, DevHarness, DEV_ACCOUNTS, DEV_BALANCE,
+    create_dev_db, dev_account, dev_account_at, dev_chain_spec, DevHarness, DEV_ACCOUNTS,
+    DEV_BALANCE,
 };
+pub use diff::{AccountDiff, Change, StateDiff};
+pub use difference::Difference;
+pub use differential::DifferentialHarness;
+pub use genesis::{create_genesis_db, IntoGenesis};
 pub use harness::{EvmTestHarness, EvmTestHarnessBuilder};
-pub use result::{EvmComparison, HarnessExecutionResult};
+pub use profile::{FrameGas, GasProfile, GasProfiler, OpcodeGas};
+pub use result::{EvmComparison, HarnessExecutionResult, SimulationOptions, SimulationResult};
+pub use revert::RevertReason;
+pub use snapshot::{SnapshotDb, SnapshotId, StateSnapshot};
+pub use spec::spec_id_at;
+#[cfg(feature = "fixtures")]
+pub use state_dump::DumpDb;
+pub use tx::{
+    TxBuilder, EIP1559_TX_TYPE, EIP2930_TX_TYPE, EIP4844_TX_TYPE, EIP7702_TX_TYPE,
+    LEGACY_TX_TYPE,
+};
@@ -0,0 +1,328 @@
+//! Gas profiling by opcode, contract and call frame
+
+use super::{EvmTestHarness, HarnessExecutionResult};
+use crate::Result;
+use reth::revm::{
+    bytecode::opcode::OpCode,
+    context::TxEnv,
+    context_interface::{result::HaltReason, CreateScheme},
+    database_interface::DatabaseCommit,
+    interpreter::{
+        interpreter_types::Jumps, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome,
+        Gas, Interpreter,
+    },
+    primitives::{hardfork::SpecId, Address},
+    Inspector,
+};
+use reth_evm::{Database, EvmFactory};
+use std::{collections::BTreeMap, fmt};
+
+/// Gas spent by a single opcode
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fixtures", derive(serde::Serialize))]
+pub struct OpcodeGas {
+    /// Opcode mnemonic, or its hex value if unknown
+    pub name: String,
+    /// Number of times the opcode was executed
+    pub count: u64,
+    /// Gas spent, excluding gas used by child frames
+    pub gas: u64,
+}
+
+/// Gas spent in a single call frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "fixtures", derive(serde::Serialize))]
+pub struct FrameGas {
+    /// Call depth, 0 for the transaction's top-level frame
+    pub depth: usize,
+    /// Frame type (CALL, STATICCALL, DELEGATECALL, CALLCODE, CREATE or CREATE2)
+    pub kind: String,
+    /// Address of the executed code, `None` for failed creations
+    pub address: Option<Address>,
+    /// Gas used by the frame and its children
+    pub gas_used: u64,
+    /// Gas used by the frame itself
+    pub self_gas: u64,
+}
+
+/// Gas profile of one or more transactions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "fixtures", derive(serde::Serialize))]
+pub struct GasProfile {
+    /// Total gas used by the profiled transactions, including intrinsic gas
+    pub gas_used: u64,
+    /// Gas by opcode
+    pub opcodes: BTreeMap<u8, OpcodeGas>,
+    /// Gas by executed code address, excluding gas used by child frames
+    pub contracts: BTreeMap<Address, u64>,
+    /// Call frames in execution order
+    pub frames: Vec<FrameGas>,
+}
+
+impl GasProfile {
+    /// Opcodes sorted by gas spent, most expensive first
+    pub fn opcodes_by_gas(&self) -> Vec<(u8, &OpcodeGas)> {
+        let mut opcodes: Vec<_> = self.opcodes.iter().map(|(op, gas)| (*op, gas)).collect();
+        opcodes.sort_by(|a, b| b.1.gas.cmp(&a.1.gas).then(a.0.cmp(&b.0)));
+        opcodes
+    }
+
+    /// Contracts sorted by gas spent, most expensive first
+    pub fn contracts_by_gas(&self) -> Vec<(Address, u64)> {
+        let mut contracts: Vec<_> = self.contracts.iter().map(|(a, gas)| (*a, *gas)).collect();
+        contracts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        contracts
+    }
+
+    /// Render the profile as JSON
+    #[cfg(feature = "fixtures")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| crate::Error::fixture(e.to_string()))
+    }
+}
+
+impl fmt::Display for GasProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Total gas used: {}", self.gas_used)?;
+
+        writeln!(f, "\n{:<16} {:>10} {:>12}", "Opcode", "Count", "Gas")?;
+        for (_, opcode) in self.opcodes_by_gas() {
+            writeln!(
+                f,
+                "{:<16} {:>10} {:>12}",
+                opcode.name, opcode.count, opcode.gas
+            )?;
+        }
+
+        writeln!(f, "\n{:<42} {:>12}", "Contract", "Gas")?;
+        for (address, gas) in self.contracts_by_gas() {
+            writeln!(f, "{:<42} {:>12}", address.to_string(), gas)?;
+        }
+
+        writeln!(f, "\nCall frames (gas used / self gas)")?;
+        for frame in &self.frames {
+            let address = frame
+                .address
+                .map_or_else(|| "<failed>".to_string(), |a| a.to_string());
+            writeln!(
+                f,
+                "{:indent$}{} {} {} / {}",
+                "",
+                frame.kind,
+                address,
+                frame.gas_used,
+                frame.self_gas,
+                indent = frame.depth * 2
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A frame that has not returned yet
+#[derive(Debug)]
+struct ActiveFrame {
+    /// Index into `GasProfile::frames`
+    index: usize,
+    /// Last executed opcode and the gas remaining before it
+    pending: Option<(u8, u64)>,
+    /// Gas used by children since the last opcode
+    child_gas: u64,
+    /// Gas used by all children
+    total_child_gas: u64,
+}
+
+/// Inspector aggregating gas into a [`GasProfile`]
+///
+/// Can be reused across transactions to profile them together.
+#[derive(Debug, Default)]
+pub struct GasProfiler {
+    profile: GasProfile,
+    frames: Vec<ActiveFrame>,
+}
+
+impl GasProfiler {
+    /// Create an empty profiler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consume the profiler and return the collected profile
+    pub fn into_profile(self) -> GasProfile {
+        self.profile
+    }
+
+    fn enter(&mut self, kind: &str, address: Option<Address>) {
+        self.profile.frames.push(FrameGas {
+            depth: self.frames.len(),
+            kind: kind.to_string(),
+            address,
+            gas_used: 0,
+            self_gas: 0,
+        });
+        self.frames.push(ActiveFrame {
+            index: self.profile.frames.len() - 1,
+            pending: None,
+            child_gas: 0,
+            total_child_gas: 0,
+        });
+    }
+
+    /// Charge the previous opcode of the current frame with the gas spent since it started
+    fn settle(&mut self, gas_remaining: u64) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let Some((opcode, gas_before)) = frame.pending.take() else {
+            return;
+        };
+
+        // Calls and creates spend their child's gas, which is accounted to the child
+        let cost = gas_before
+            .saturating_sub(gas_remaining)
+            .saturating_sub(frame.child_gas);
+        frame.child_gas = 0;
+
+        let entry = self
+            .profile
+            .opcodes
+            .entry(opcode)
+            .or_insert_with(|| OpcodeGas {
+                name: OpCode::new(opcode)
+                    .map_or_else(|| format!("0x{:02x}", opcode), |op| op.as_str().to_string()),
+                count: 0,
+                gas: 0,
+            });
+        entry.count += 1;
+        entry.gas += cost;
+    }
+
+    fn exit(&mut self, gas: &Gas, address: Option<Address>) {
+        self.settle(gas.remaining());
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+
+        let record = &mut self.profile.frames[frame.index];
+        if record.address.is_none() {
+            record.address = address;
+        }
+        record.gas_used = gas.spent();
+        record.self_gas = record.gas_used.saturating_sub(frame.total_child_gas);
+        if let Some(address) = record.address {
+            *self.profile.contracts.entry(address).or_default() += record.self_gas;
+        }
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.child_gas += record.gas_used;
+            parent.total_child_gas += record.gas_used;
+        }
+    }
+}
+
+impl<CTX> Inspector<CTX> for GasProfiler {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut CTX) {
+        let gas_remaining = interp.gas.remaining();
+        self.settle(gas_remaining);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.pending = Some((interp.bytecode.opcode(), gas_remaining));
+        }
+    }
+
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let kind = match inputs.scheme {
+            CallScheme::Call => "CALL",
+            CallScheme::CallCode => "CALLCODE",
+            CallScheme::DelegateCall => "DELEGATECALL",
+            CallScheme::StaticCall => "STATICCALL",
+        };
+        self.enter(kind, Some(inputs.bytecode_address));
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.exit(&outcome.result.gas, None);
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let kind = match inputs.scheme {
+            CreateScheme::Create2 { .. } => "CREATE2",
+            _ => "CREATE",
+        };
+        self.enter(kind, None);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.exit(&outcome.result.gas, outcome.address);
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+where
+    GasProfiler: for<'a> Inspector<Evm::Context<&'a mut DB>>,
+{
+    /// Execute a transaction and commit it, profiling where its gas went
+    pub fn profile_tx(&mut self, tx: TxEnv) -> Result<(HarnessExecutionResult, GasProfile)> {
+        let (mut results, profile) = self.profile_txs([tx])?;
+        Ok((results.remove(0), profile))
+    }
+
+    /// Execute and commit transactions in order, aggregating their gas into one profile
+    pub fn profile_txs(
+        &mut self,
+        txs: impl IntoIterator<Item = TxEnv>,
+    ) -> Result<(Vec<HarnessExecutionResult>, GasProfile)> {
+        let mut profiler = GasProfiler::new();
+        let mut results = Vec::new();
+
+        for tx in txs {
+            let (result, next) = self.execute_tx_with_inspector(tx, profiler)?;
+            profiler = next;
+            profiler.profile.gas_used += result.gas_used;
+            results.push(result);
+        }
+
+        Ok((results, profiler.into_profile()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::test_utils::{call_tx, dev_harness_with_code, CONTRACT, STATICCALL_IDENTITY};
+
+    #[test]
+    fn test_profile_attributes_gas_to_opcodes_and_frames() {
+        let mut harness = dev_harness_with_code(&STATICCALL_IDENTITY);
+        let identity = Address::with_last_byte(4);
+        let tx = harness.fill_tx(call_tx(CONTRACT)).unwrap();
+
+        let (result, profile) = harness.profile_tx(tx).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(profile.gas_used, result.gas_used);
+        assert_eq!(profile.opcodes[&0x60].count, 5);
+        assert_eq!(profile.opcodes[&0x60].gas, 15);
+        // Warm access only, the precompile's own gas is charged to its frame
+        assert_eq!(profile.opcodes[&0xfa].gas, 100);
+        assert_eq!(profile.frames.len(), 2);
+        assert_eq!(profile.frames[1].kind, "STATICCALL");
+        assert_eq!(profile.frames[1].address, Some(identity));
+        assert_eq!(profile.contracts[&identity], 15);
+        assert_eq!(
+            profile.frames[0].gas_used,
+            profile.frames[0].self_gas + profile.frames[1].gas_used
+        );
+        assert!(profile.to_string().contains("STATICCALL"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Execution of signed, EIP-2718 encoded transactions
+
+use super::{EvmTestHarness, HarnessExecutionResult};
+use crate::{Error, Result};
+use alloy_consensus::{transaction::SignerRecoverable, Transaction, TxEnvelope};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_evm::FromRecoveredTx;
+use reth::revm::{
+    context::TxEnv, context_interface::result::HaltReason, database_interface::DatabaseCommit,
+    primitives::hardfork::SpecId,
+};
+use reth_evm::{Database, EvmFactory};
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// Decode a signed transaction, recover its signer and execute it
+    ///
+    /// Accepts the EIP-2718 encoding used by `eth_sendRawTransaction` and block bodies.
+    pub fn execute_raw_tx(&mut self, raw: &[u8]) -> Result<HarnessExecutionResult> {
+        let tx = self.decode_raw_tx(raw)?;
+        self.execute_tx(tx)
+    }
+
+    /// Decode a signed transaction into a `TxEnv` with its recovered signer as caller
+    ///
+    /// Fails if the transaction is signed for a different chain. Legacy transactions
+    /// without EIP-155 replay protection are accepted.
+    pub fn decode_raw_tx(&self, raw: &[u8]) -> Result<TxEnv> {
+        let mut buf = raw;
+        let envelope = TxEnvelope::decode_2718(&mut buf)
+            .map_err(|e| Error::invalid_transaction(format!("Failed to decode: {}", e)))?;
+        if !buf.is_empty() {
+            return Err(Error::invalid_transaction(format!(
+                "{} trailing bytes after transaction",
+                buf.len()
+            )));
+        }
+
+        if let Some(chain_id) = envelope.chain_id() {
+            if chain_id != self.chain_id() {
+                return Err(Error::invalid_transaction(format!(
+                    "Chain ID mismatch: transaction={}, harness={}",
+                    chain_id,
+                    self.chain_id()
+                )));
+            }
+        }
+
+        let signer = envelope
+            .recover_signer()
+            .map_err(|e| Error::invalid_transaction(format!("Failed to recover signer: {}", e)))?;
+
+        Ok(TxEnv::from_recovered_tx(&envelope, signer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{dev_account, DevHarness};
+    use alloy_consensus::{SignableTransaction, TxEip1559};
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_evm::eth::EthEvmFactory;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use reth::revm::{
+        database_interface::EmptyDB,
+        primitives::{Address, TxKind, U256},
+        State,
+    };
+
+    /// Private key of the first dev account
+    const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn signed_transfer(chain_id: u64) -> Vec<u8> {
+        let tx = TxEip1559 {
+            chain_id,
+            nonce: 0,
+            gas_limit: 21_000,
+            max_fee_per_gas: 1_000_000_000,
+            max_priority_fee_per_gas: 0,
+            to: TxKind::Call(Address::with_last_byte(0xaa)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        let signer: PrivateKeySigner = DEV_KEY.parse().unwrap();
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).unwrap();
+
+        TxEnvelope::from(tx.into_signed(signature)).encoded_2718()
+    }
+
+    #[test]
+    fn test_execute_raw_tx_recovers_signer() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let raw = signed_transfer(harness.chain_id());
+
+        assert_eq!(harness.decode_raw_tx(&raw).unwrap().caller, dev_account());
+        assert!(harness.execute_raw_tx(&raw).unwrap().is_success());
+        assert_eq!(harness.get_nonce(dev_account()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rejects_wrong_chain_id_and_malformed_input() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let raw = signed_transfer(harness.chain_id() + 1);
+
+        assert!(harness.execute_raw_tx(&raw).is_err());
+        assert!(harness.execute_raw_tx(&[0x02, 0xc0]).is_err());
+    }
+}
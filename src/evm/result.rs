@@ -1,6 +1,17 @@
 //! EVM execution result types
 
-use reth::revm::primitives::{Bytes, Log};
+use super::{
+    diff::StateDiff,
+    difference::{self, Difference},
+    revert::RevertReason,
+};
+use alloy_rpc_types_trace::geth::CallFrame;
+use reth::revm::{
+    context_interface::result::{ExecutionResult, HaltReason},
+    primitives::{Address, Bytes, Log},
+    state::EvmState,
+};
+use std::fmt;
 
 /// Result of EVM execution
 #[derive(Debug, Clone)]
@@ -15,8 +26,16 @@ pub struct HarnessExecutionResult {
     pub output: Bytes,
     /// Logs emitted
     pub logs: Vec<Log>,
-    /// Revert reason if execution failed
-    pub revert_reason: Option<String>,
+    /// Revert or halt reason if execution failed
+    pub revert_reason: Option<RevertReason>,
+    /// Address of the contract deployed by a successful CREATE or CREATE2 transaction
+    pub created_address: Option<Address>,
+    /// State changes made by the transaction
+    pub state_diff: StateDiff,
+    /// EIP-3155 trace lines, empty unless the transaction was traced
+    pub trace: Vec<String>,
+    /// Geth `callTracer`-style call tree, if the transaction was call traced
+    pub call_trace: Option<CallFrame>,
 }
 
 impl HarnessExecutionResult {
@@ -29,18 +48,26 @@ impl HarnessExecutionResult {
             output,
             logs: Vec::new(),
             revert_reason: None,
+            created_address: None,
+            state_diff: StateDiff::default(),
+            trace: Vec::new(),
+            call_trace: None,
         }
     }
 
     /// Create a failed execution result
-    pub fn revert(gas_used: u64, reason: impl Into<String>) -> Self {
+    pub fn revert(gas_used: u64, reason: RevertReason) -> Self {
         Self {
             success: false,
             gas_used,
             gas_refunded: 0,
             output: Bytes::new(),
             logs: Vec::new(),
-            revert_reason: Some(reason.into()),
+            revert_reason: Some(reason),
+            created_address: None,
+            state_diff: StateDiff::default(),
+            trace: Vec::new(),
+            call_trace: None,
         }
     }
 
@@ -54,9 +81,26 @@ impl HarnessExecutionResult {
         !self.success
     }
 
+    /// Check if execution halted (out of gas, invalid opcode, ...)
+    pub fn is_halt(&self) -> bool {
+        self.revert_reason
+            .as_ref()
+            .is_some_and(RevertReason::is_halt)
+    }
+
     /// Get the revert reason if execution failed
-    pub fn revert_reason(&self) -> Option<&str> {
-        self.revert_reason.as_deref()
+    pub fn revert_reason(&self) -> Option<&RevertReason> {
+        self.revert_reason.as_ref()
+    }
+
+    /// Get the address of the deployed contract for a successful creation
+    pub fn created_address(&self) -> Option<Address> {
+        self.created_address
+    }
+
+    /// Get the logs emitted by a specific address
+    pub fn logs_from(&self, address: Address) -> impl Iterator<Item = &Log> {
+        self.logs.iter().filter(move |log| log.address == address)
     }
 
     /// Add a log to the execution result
@@ -65,6 +109,30 @@ impl HarnessExecutionResult {
         self
     }
 
+    /// Set the deployed contract address
+    pub fn with_created_address(mut self, address: Address) -> Self {
+        self.created_address = Some(address);
+        self
+    }
+
+    /// Set the state diff
+    pub fn with_state_diff(mut self, state_diff: StateDiff) -> Self {
+        self.state_diff = state_diff;
+        self
+    }
+
+    /// Set the EIP-3155 trace lines
+    pub fn with_trace(mut self, trace: Vec<String>) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Set the call tree
+    pub fn with_call_trace(mut self, call_trace: CallFrame) -> Self {
+        self.call_trace = Some(call_trace);
+        self
+    }
+
     /// Set gas refunded
     pub fn with_gas_refunded(mut self, gas_refunded: u64) -> Self {
         self.gas_refunded = gas_refunded;
@@ -72,6 +140,93 @@ impl HarnessExecutionResult {
     }
 }
 
+/// Converts any EVM's result; halt reasons that don't convert into an Ethereum
+/// [`HaltReason`] become [`RevertReason::CustomHalt`]
+impl<H: TryInto<HaltReason> + Clone + fmt::Debug> From<ExecutionResult<H>>
+    for HarnessExecutionResult
+{
+    fn from(result: ExecutionResult<H>) -> Self {
+        match result {
+            ExecutionResult::Success {
+                output,
+                gas_used,
+                gas_refunded,
+                logs,
+                ..
+            } => Self {
+                success: true,
+                gas_used,
+                gas_refunded,
+                created_address: output.address().copied(),
+                output: output.into_data(),
+                logs,
+                revert_reason: None,
+                state_diff: StateDiff::default(),
+                trace: Vec::new(),
+                call_trace: None,
+            },
+            ExecutionResult::Revert { output, gas_used } => Self {
+                success: false,
+                gas_used,
+                gas_refunded: 0,
+                revert_reason: Some(RevertReason::decode(&output)),
+                output,
+                logs: Vec::new(),
+                created_address: None,
+                state_diff: StateDiff::default(),
+                trace: Vec::new(),
+                call_trace: None,
+            },
+            ExecutionResult::Halt { reason, gas_used } => Self {
+                success: false,
+                gas_used,
+                gas_refunded: 0,
+                output: Bytes::new(),
+                logs: Vec::new(),
+                revert_reason: Some(match reason.clone().try_into() {
+                    Ok(reason) => RevertReason::Halt(reason),
+                    Err(_) => RevertReason::CustomHalt(format!("{:?}", reason)),
+                }),
+                created_address: None,
+                state_diff: StateDiff::default(),
+                trace: Vec::new(),
+                call_trace: None,
+            },
+        }
+    }
+}
+
+/// Result of executing a transaction without committing its state
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    /// The execution result
+    pub result: HarnessExecutionResult,
+    /// State changes that would have been committed
+    pub state: EvmState,
+}
+
+/// Validation checks to relax when simulating a transaction
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulationOptions {
+    /// Skip the sender balance check
+    pub disable_balance_check: bool,
+    /// Skip the sender nonce check
+    pub disable_nonce_check: bool,
+    /// Skip the base fee check, allowing a zero gas price
+    pub disable_base_fee: bool,
+}
+
+impl SimulationOptions {
+    /// Options matching `eth_call`, with all checks disabled
+    pub fn eth_call() -> Self {
+        Self {
+            disable_balance_check: true,
+            disable_nonce_check: true,
+            disable_base_fee: true,
+        }
+    }
+}
+
 /// Comparison between two EVM execution results
 #[derive(Debug, Clone)]
 pub struct EvmComparison {
@@ -82,73 +237,219 @@ pub struct EvmComparison {
     /// Whether the results match
     pub matches: bool,
     /// Differences found
-    pub differences: Vec<String>,
+    pub differences: Vec<Difference>,
+    /// Accounts whose balance moves with gas, the transaction caller and block beneficiary
+    pub fee_accounts: Vec<Address>,
 }
 
 impl EvmComparison {
     /// Create a new comparison
-    pub fn new(test_result: HarnessExecutionResult, reference_result: HarnessExecutionResult) -> Self {
-        let mut differences = Vec::new();
-        let mut matches = true;
-
-        if test_result.success != reference_result.success {
-            differences.push(format!(
-                "Execution status differs: test={}, reference={}",
-                test_result.success, reference_result.success
-            ));
-            matches = false;
-        }
-
-        if test_result.gas_used != reference_result.gas_used {
-            differences.push(format!(
-                "Gas used differs: test={}, reference={}",
-                test_result.gas_used, reference_result.gas_used
-            ));
-            matches = false;
-        }
-
-        if test_result.output != reference_result.output {
-            differences.push(format!(
-                "Output differs: test={}, reference={}",
-                test_result.output, reference_result.output
-            ));
-            matches = false;
-        }
-
-        if test_result.logs.len() != reference_result.logs.len() {
-            differences.push(format!(
-                "Log count differs: test={}, reference={}",
-                test_result.logs.len(),
-                reference_result.logs.len()
-            ));
-            matches = false;
-        }
+    pub fn new(
+        test_result: HarnessExecutionResult,
+        reference_result: HarnessExecutionResult,
+    ) -> Self {
+        let differences = difference::compare(&test_result, &reference_result);
 
         Self {
             test_result,
             reference_result,
-            matches,
+            matches: differences.is_empty(),
             differences,
+            fee_accounts: Vec::new(),
         }
     }
 
+    /// Set the fee paying caller and fee receiving beneficiary for [`Self::is_gas_only`]
+    pub fn with_fee_accounts(mut self, caller: Address, beneficiary: Address) -> Self {
+        self.fee_accounts = vec![caller, beneficiary];
+        self
+    }
+
     /// Check if the results match
     pub fn is_match(&self) -> bool {
         self.matches
     }
 
     /// Get the differences
-    pub fn differences(&self) -> &[String] {
+    pub fn differences(&self) -> &[Difference] {
         &self.differences
     }
 
+    /// Check if the results differ only in gas
+    ///
+    /// Balance differences of the fee accounts count as gas differences when gas used
+    /// or refunded also differs, since fees move with gas. Balance differences of any
+    /// other account do not.
+    pub fn is_gas_only(&self) -> bool {
+        self.differences.iter().any(Difference::is_gas)
+            && self.differences.iter().all(|d| match d {
+                Difference::Balance { address, .. } => self.fee_accounts.contains(address),
+                d => d.is_gas(),
+            })
+    }
+
+    /// Get the differences not covered by an allowlist
+    pub fn differences_except(&self, allowed: impl Fn(&Difference) -> bool) -> Vec<&Difference> {
+        self.differences.iter().filter(|d| !allowed(d)).collect()
+    }
+
     /// Assert that results match, panicking with details if they don't
     pub fn assert_match(&self) {
-        if !self.matches {
-            panic!(
-                "EVM execution results differ:\n{}",
-                self.differences.join("\n")
-            );
+        self.assert_match_except(|_| false);
+    }
+
+    /// Assert that results match apart from allowlisted differences
+    pub fn assert_match_except(&self, allowed: impl Fn(&Difference) -> bool) {
+        let unexpected = self.differences_except(allowed);
+        if !unexpected.is_empty() {
+            let lines: Vec<_> = unexpected.iter().map(|d| format!("  - {}", d)).collect();
+            panic!("EVM execution results differ:\n{}", lines.join("\n"));
+        }
+    }
+}
+
+impl fmt::Display for EvmComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.matches {
+            return write!(f, "results match");
         }
+
+        write!(f, "{} differences:", self.differences.len())?;
+        for difference in &self.differences {
+            write!(f, "\n  - {}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{AccountDiff, Change};
+    use reth::revm::primitives::{LogData, B256, U256};
+
+    fn log(address: Address, topic: u8, data: &'static [u8]) -> Log {
+        Log {
+            address,
+            data: LogData::new_unchecked(
+                vec![B256::with_last_byte(topic)],
+                Bytes::from_static(data),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_comparison_detects_log_content_differences() {
+        let emitter = Address::with_last_byte(0x42);
+        let test =
+            HarnessExecutionResult::success(21_000, Bytes::new()).with_log(log(emitter, 1, b"a"));
+        let reference =
+            HarnessExecutionResult::success(21_000, Bytes::new()).with_log(log(emitter, 2, b"b"));
+
+        let comparison = EvmComparison::new(test, reference);
+
+        assert!(!comparison.is_match());
+        assert_eq!(comparison.differences().len(), 2);
+        assert!(matches!(
+            comparison.differences()[0],
+            Difference::LogTopics { index: 0, .. }
+        ));
+        assert!(matches!(
+            comparison.differences()[1],
+            Difference::LogData { index: 0, .. }
+        ));
+        assert!(!comparison.is_gas_only());
+    }
+
+    #[test]
+    fn test_gas_only_differences_can_be_allowlisted() {
+        let test = HarnessExecutionResult::success(21_000, Bytes::new());
+        let reference = HarnessExecutionResult::success(21_100, Bytes::new());
+
+        let comparison = EvmComparison::new(test, reference);
+
+        assert!(comparison.is_gas_only());
+        assert!(comparison
+            .to_string()
+            .contains("gas used: test=21000, reference=21100 (-100)"));
+        comparison.assert_match_except(Difference::is_gas);
+    }
+
+    #[test]
+    fn test_only_fee_account_balances_count_as_gas() {
+        let caller = Address::with_last_byte(0xaa);
+        let beneficiary = Address::with_last_byte(0xbb);
+        let receiver = Address::with_last_byte(0xcc);
+        // Results with 100 more gas used on the test side and a differing balance
+        let comparison = |address: Address| {
+            let balance_diff = |after: u64| {
+                let mut state_diff = StateDiff::default();
+                state_diff.accounts.insert(
+                    address,
+                    AccountDiff {
+                        balance: Change::new(U256::from(100), U256::from(after)),
+                        ..Default::default()
+                    },
+                );
+                HarnessExecutionResult::success(21_000, Bytes::new()).with_state_diff(state_diff)
+            };
+            let mut test = balance_diff(90);
+            test.gas_used = 21_100;
+            EvmComparison::new(test, balance_diff(80)).with_fee_accounts(caller, beneficiary)
+        };
+
+        assert!(comparison(caller).is_gas_only());
+        assert!(comparison(beneficiary).is_gas_only());
+        assert!(!comparison(receiver).is_gas_only());
+    }
+
+    #[test]
+    fn test_logs_from_filters_by_address() {
+        let emitter = Address::with_last_byte(0x42);
+        let result = HarnessExecutionResult::success(21_000, Bytes::new())
+            .with_log(log(emitter, 1, b""))
+            .with_log(log(Address::ZERO, 1, b""));
+
+        assert_eq!(result.logs_from(emitter).count(), 1);
+    }
+
+    #[test]
+    fn test_halt_reasons_keep_ethereum_type() {
+        #[derive(Debug, Clone)]
+        enum CustomHalt {
+            Base(HaltReason),
+            Deposit,
+        }
+
+        impl TryFrom<CustomHalt> for HaltReason {
+            type Error = CustomHalt;
+
+            fn try_from(reason: CustomHalt) -> Result<Self, Self::Error> {
+                match reason {
+                    CustomHalt::Base(reason) => Ok(reason),
+                    reason => Err(reason),
+                }
+            }
+        }
+
+        let halt = |reason| {
+            HarnessExecutionResult::from(ExecutionResult::Halt {
+                reason,
+                gas_used: 100,
+            })
+        };
+
+        let result = halt(CustomHalt::Base(HaltReason::OpcodeNotFound));
+        assert!(matches!(
+            result.revert_reason().and_then(RevertReason::halt_reason),
+            Some(HaltReason::OpcodeNotFound)
+        ));
+
+        let result = halt(CustomHalt::Deposit);
+        assert!(result.is_halt());
+        assert_eq!(
+            result.revert_reason(),
+            Some(&RevertReason::CustomHalt("Deposit".to_string()))
+        );
     }
 }
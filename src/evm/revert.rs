@@ -0,0 +1,152 @@
+//! Structured revert and halt reasons
+
+use alloy_sol_types::{Panic, PanicKind, Revert, SolError};
+use reth::revm::{
+    context_interface::result::HaltReason,
+    primitives::{Bytes, FixedBytes, U256},
+};
+use std::fmt;
+
+/// Reason a transaction did not complete successfully
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// Revert with a Solidity `Error(string)` message
+    Error(String),
+    /// Revert with a Solidity `Panic(uint256)` code
+    Panic {
+        /// The raw panic code
+        code: U256,
+        /// The known meaning of the panic code, if any
+        kind: Option<PanicKind>,
+    },
+    /// Revert with a custom error
+    Custom {
+        /// The 4-byte error selector
+        selector: FixedBytes<4>,
+        /// The ABI-encoded error arguments
+        data: Bytes,
+    },
+    /// Revert without any data
+    Empty,
+    /// Revert data that is too short to carry a selector
+    Raw(Bytes),
+    /// Execution halted before completing
+    Halt(HaltReason),
+    /// Execution halted for a custom EVM's reason that has no Ethereum equivalent,
+    /// rendered via `Debug`
+    CustomHalt(String),
+}
+
+impl RevertReason {
+    /// Decode revert output data into a structured reason
+    pub fn decode(output: &[u8]) -> Self {
+        if output.is_empty() {
+            return Self::Empty;
+        }
+
+        if output.len() < 4 {
+            return Self::Raw(Bytes::copy_from_slice(output));
+        }
+
+        let (selector, data) = output.split_at(4);
+
+        if selector == Revert::SELECTOR {
+            if let Ok(revert) = Revert::abi_decode(output) {
+                return Self::Error(revert.reason);
+            }
+        } else if selector == Panic::SELECTOR {
+            if let Ok(panic) = Panic::abi_decode(output) {
+                return Self::Panic {
+                    code: panic.code,
+                    kind: panic.kind(),
+                };
+            }
+        }
+
+        Self::Custom {
+            selector: FixedBytes::from_slice(selector),
+            data: Bytes::copy_from_slice(data),
+        }
+    }
+
+    /// Get the `Error(string)` message if this is a string revert
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Error(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Get the halt reason if execution halted for an Ethereum reason
+    pub fn halt_reason(&self) -> Option<&HaltReason> {
+        match self {
+            Self::Halt(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Check if execution halted rather than reverted
+    pub fn is_halt(&self) -> bool {
+        matches!(self, Self::Halt(_) | Self::CustomHalt(_))
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(message) => write!(f, "execution reverted: {}", message),
+            Self::Panic {
+                code,
+                kind: Some(kind),
+            } => write!(f, "panic: {} (0x{:x})", kind, code),
+            Self::Panic { code, kind: None } => write!(f, "panic: unknown code 0x{:x}", code),
+            Self::Custom { selector, data } => {
+                write!(f, "custom error {}: {}", selector, data)
+            }
+            Self::Empty => write!(f, "execution reverted"),
+            Self::Raw(data) => write!(f, "execution reverted: {}", data),
+            Self::Halt(reason) => write!(f, "execution halted: {:?}", reason),
+            Self::CustomHalt(reason) => write!(f, "execution halted: {}", reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_error_string() {
+        let output = Revert::from("insufficient balance").abi_encode();
+
+        let reason = RevertReason::decode(&output);
+
+        assert_eq!(
+            reason,
+            RevertReason::Error("insufficient balance".to_string())
+        );
+        assert_eq!(reason.message(), Some("insufficient balance"));
+    }
+
+    #[test]
+    fn test_decode_panic_and_custom_error() {
+        let output = Panic::from(PanicKind::DivisionByZero).abi_encode();
+        assert_eq!(
+            RevertReason::decode(&output),
+            RevertReason::Panic {
+                code: U256::from(0x12),
+                kind: Some(PanicKind::DivisionByZero),
+            }
+        );
+
+        let output = [0xde, 0xad, 0xbe, 0xef, 0x01];
+        assert_eq!(
+            RevertReason::decode(&output),
+            RevertReason::Custom {
+                selector: FixedBytes::new([0xde, 0xad, 0xbe, 0xef]),
+                data: Bytes::from_static(&[0x01]),
+            }
+        );
+        assert_eq!(RevertReason::decode(&[]), RevertReason::Empty);
+    }
+}
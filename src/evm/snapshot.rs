@@ -0,0 +1,80 @@
+//! Snapshot and revert support for harness state
+
+use reth::revm::{
+    context::{BlockEnv, CfgEnv},
+    db::{BundleState, CacheDB, CacheState, TransitionState},
+    primitives::hardfork::SpecId,
+    State,
+};
+
+/// Identifier of a harness state snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotId(pub(crate) u64);
+
+impl SnapshotId {
+    /// Get the numeric value of the snapshot ID
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Database that can capture and restore its full state
+pub trait SnapshotDb {
+    /// Captured database state
+    type Snapshot: Send + Sync + 'static;
+
+    /// Capture the current database state
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Restore a previously captured database state
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
+
+/// Captured state of a `State` database
+#[derive(Debug, Clone)]
+pub struct StateSnapshot<DB> {
+    cache: CacheState,
+    database: DB,
+    transition_state: Option<TransitionState>,
+    bundle_state: BundleState,
+}
+
+impl<DB: Clone + Send + Sync + 'static> SnapshotDb for State<DB> {
+    type Snapshot = StateSnapshot<DB>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        StateSnapshot {
+            cache: self.cache.clone(),
+            database: self.database.clone(),
+            transition_state: self.transition_state.clone(),
+            bundle_state: self.bundle_state.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.cache = snapshot.cache;
+        self.database = snapshot.database;
+        self.transition_state = snapshot.transition_state;
+        self.bundle_state = snapshot.bundle_state;
+    }
+}
+
+impl<DB: Clone + Send + Sync + 'static> SnapshotDb for CacheDB<DB> {
+    type Snapshot = Self;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        *self = snapshot;
+    }
+}
+
+/// Full harness snapshot: database plus block and config environment
+pub(crate) struct HarnessSnapshot<S> {
+    pub(crate) db: S,
+    pub(crate) block_env: BlockEnv,
+    pub(crate) cfg_env: CfgEnv,
+    pub(crate) spec_override: Option<SpecId>,
+}
@@ -0,0 +1,69 @@
+//! Hardfork resolution from a chain specification
+
+use reth::revm::{context_interface::block::BlobExcessGasAndPrice, primitives::hardfork::SpecId};
+use reth_chainspec::{ChainSpec, EthChainSpec, Hardforks};
+use reth_evm_ethereum::revm_spec_by_timestamp_and_block_number;
+
+/// Resolve the active spec ID for a block from the chain's hardfork schedule
+///
+/// Falls back to `FRONTIER` when no hardfork is active yet, e.g. for a chain spec
+/// without any hardforks.
+pub fn spec_id_at(chain_spec: &ChainSpec, block_number: u64, timestamp: u64) -> SpecId {
+    let any_active = chain_spec.forks_iter().any(|(_, condition)| {
+        condition.active_at_block(block_number) || condition.active_at_timestamp(timestamp)
+    });
+    if !any_active {
+        return SpecId::FRONTIER;
+    }
+    revm_spec_by_timestamp_and_block_number(chain_spec, timestamp, block_number)
+}
+
+/// Price a header's excess blob gas with the blob parameters active at `timestamp`
+pub(crate) fn blob_excess_gas_and_price(
+    chain_spec: &ChainSpec,
+    excess_blob_gas: Option<u64>,
+    timestamp: u64,
+) -> Option<BlobExcessGasAndPrice> {
+    excess_blob_gas
+        .zip(chain_spec.blob_params_at_timestamp(timestamp))
+        .map(|(excess_blob_gas, params)| BlobExcessGasAndPrice {
+            excess_blob_gas,
+            blob_gasprice: params.calc_blob_fee(excess_blob_gas),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ChainSpecBuilder, EthereumHardfork, ForkCondition, MAINNET};
+
+    #[test]
+    fn test_mainnet_spec_ids() {
+        assert_eq!(spec_id_at(&MAINNET, 0, 0), SpecId::FRONTIER);
+        assert_eq!(spec_id_at(&MAINNET, 12_965_000, 0), SpecId::LONDON);
+        assert_eq!(
+            spec_id_at(&MAINNET, 15_537_394, 1_663_224_162),
+            SpecId::MERGE
+        );
+        assert_eq!(
+            spec_id_at(&MAINNET, 19_426_587, 1_710_338_135),
+            SpecId::CANCUN
+        );
+    }
+
+    #[test]
+    fn test_timestamp_fork_transition() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .cancun_activated()
+            .with_fork(EthereumHardfork::Prague, ForkCondition::Timestamp(1_000))
+            .build();
+
+        assert_eq!(spec_id_at(&chain_spec, 0, 999), SpecId::CANCUN);
+        assert_eq!(spec_id_at(&chain_spec, 0, 1_000), SpecId::PRAGUE);
+    }
+
+    #[test]
+    fn test_chain_spec_without_hardforks() {
+        assert_eq!(spec_id_at(&ChainSpec::default(), 0, 0), SpecId::FRONTIER);
+    }
+}
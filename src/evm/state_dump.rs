@@ -0,0 +1,183 @@
+//! Dumping and loading full harness state in anvil's `dumpState` format
+
+use super::{CheatDb, EvmTestHarness};
+use crate::{
+    fixtures::{AccountState, StateDump},
+    Result,
+};
+use reth::revm::{
+    bytecode::Bytecode,
+    context::TxEnv,
+    context_interface::result::HaltReason,
+    database_interface::DatabaseCommit,
+    db::CacheDB,
+    primitives::{hardfork::SpecId, Address, HashMap, B256, KECCAK_EMPTY, U256},
+    state::AccountInfo,
+    State,
+};
+use reth_evm::{Database, EvmFactory};
+use std::collections::BTreeMap;
+
+/// Database whose in-memory accounts can be listed for a state dump
+pub trait DumpDb {
+    /// Collect every account held in memory, with its code and non-zero storage
+    ///
+    /// Accounts that only exist in an underlying database and were never loaded are
+    /// not included.
+    fn dump_accounts(&self) -> BTreeMap<Address, AccountState>;
+}
+
+impl<DB> DumpDb for State<DB> {
+    fn dump_accounts(&self) -> BTreeMap<Address, AccountState> {
+        self.cache
+            .accounts
+            .iter()
+            .filter_map(|(address, account)| {
+                let account = account.account.as_ref()?;
+                Some((
+                    *address,
+                    account_state(&account.info, &account.storage, &self.cache.contracts),
+                ))
+            })
+            .collect()
+    }
+}
+
+impl<ExtDB> DumpDb for CacheDB<ExtDB> {
+    fn dump_accounts(&self) -> BTreeMap<Address, AccountState> {
+        self.cache
+            .accounts
+            .iter()
+            .filter_map(|(address, account)| {
+                let info = account.info()?;
+                Some((
+                    *address,
+                    account_state(&info, &account.storage, &self.cache.contracts),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Convert a cached account into its dumped form
+fn account_state(
+    info: &AccountInfo,
+    storage: &HashMap<U256, U256>,
+    contracts: &HashMap<B256, Bytecode>,
+) -> AccountState {
+    let code = info
+        .code
+        .as_ref()
+        .or_else(|| contracts.get(&info.code_hash))
+        .filter(|_| info.code_hash != KECCAK_EMPTY)
+        .map(Bytecode::original_bytes)
+        .unwrap_or_default();
+
+    AccountState {
+        balance: info.balance,
+        nonce: info.nonce,
+        // Anvil expects code on every account, `0x` for EOAs
+        code: Some(code),
+        storage: storage
+            .iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(slot, value)| (*slot, *value))
+            .collect(),
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit + DumpDb,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// Capture every in-memory account and the block environment
+    ///
+    /// The result serializes to JSON that `anvil --load-state` accepts. See
+    /// [`DumpDb::dump_accounts`] for which accounts are included.
+    pub fn dump_state(&self) -> StateDump {
+        StateDump {
+            block: Some(self.block_env().clone()),
+            accounts: self.db().dump_accounts(),
+            best_block_number: Some(self.block_number()),
+        }
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit + CheatDb,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+{
+    /// Load accounts and the block environment from a state dump
+    ///
+    /// Accounts are merged into the existing state: dumped fields and storage slots
+    /// overwrite current values, everything else is kept.
+    pub fn load_state(&mut self, dump: &StateDump) -> Result<()> {
+        for (address, dumped) in &dump.accounts {
+            self.set_balance(*address, dumped.balance)?;
+            self.set_nonce(*address, dumped.nonce)?;
+            if let Some(code) = &dumped.code {
+                self.set_code(*address, code.clone())?;
+            }
+            for (slot, value) in &dumped.storage {
+                self.set_storage(*address, *slot, *value)?;
+            }
+        }
+
+        if let Some(block_env) = &dump.block {
+            self.set_block_env(block_env.clone());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::{
+        dev_account, dev_chain_spec,
+        test_utils::{CONTRACT, STORE_ONE},
+        DevHarness,
+    };
+    use alloy_evm::eth::EthEvmFactory;
+    use reth::revm::{database_interface::EmptyDB, primitives::Bytes};
+
+    #[test]
+    fn test_dump_and_load_round_trip() {
+        let mut harness = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::dev();
+        let code = Bytes::from_static(&STORE_ONE);
+        harness.set_code(CONTRACT, code.clone()).unwrap();
+        harness
+            .set_storage(CONTRACT, U256::from(1), U256::from(42))
+            .unwrap();
+        harness.set_block_number(12);
+
+        let json = harness.dump_state().to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value["block"].is_object());
+        assert!(value["accounts"][CONTRACT.to_string().to_lowercase()].is_object());
+
+        let mut loaded = EvmTestHarness::<State<EmptyDB>, EthEvmFactory>::new(
+            EthEvmFactory::default(),
+            State::builder().with_database(EmptyDB::default()).build(),
+            dev_chain_spec(),
+        );
+        loaded
+            .load_state(&StateDump::from_json(&json).unwrap())
+            .unwrap();
+
+        assert_eq!(loaded.block_number(), 12);
+        assert_eq!(
+            loaded.get_balance(dev_account()).unwrap(),
+            harness.get_balance(dev_account()).unwrap()
+        );
+        assert_eq!(loaded.get_code(CONTRACT).unwrap(), code);
+        assert_eq!(
+            loaded.get_storage(CONTRACT, U256::from(1)).unwrap(),
+            U256::from(42)
+        );
+        assert_eq!(loaded.dump_state().accounts, harness.dump_state().accounts);
+    }
+}
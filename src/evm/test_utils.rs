@@ -0,0 +1,41 @@
+//! Contracts and transactions shared by the EVM tests
+
+use super::{dev_account, DevHarness, EvmTestHarness, TxBuilder};
+use alloy_evm::eth::EthEvmFactory;
+use reth::revm::{context::TxEnv, database_interface::EmptyDB, primitives::Address, State};
+
+/// Address the test contracts are deployed at
+pub(crate) const CONTRACT: Address = Address::with_last_byte(0xcc);
+
+/// PUSH1 0 PUSH1 0 RETURN, which also works as init code for an empty contract
+pub(crate) const RETURN_EMPTY: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xf3];
+
+/// PUSH1 0 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+pub(crate) const RETURN_SLOT_0: [u8; 11] = [
+    0x60, 0x00, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+];
+
+/// PUSH1 1 PUSH1 0 SSTORE STOP
+pub(crate) const STORE_ONE: [u8; 6] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+
+/// STATICCALL(gas, 0x04, 0, 0, 0, 0) STOP, a call into the identity precompile
+pub(crate) const STATICCALL_IDENTITY: [u8; 13] = [
+    0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x04, 0x5a, 0xfa, 0x00,
+];
+
+/// Dev harness with `code` deployed at [`CONTRACT`]
+pub(crate) fn dev_harness_with_code(code: &[u8]) -> EvmTestHarness<State<EmptyDB>, EthEvmFactory> {
+    let mut harness = EvmTestHarness::dev();
+    harness.set_code(CONTRACT, code.to_vec()).unwrap();
+    harness
+}
+
+/// Unfilled EIP-1559 call from the first dev account
+pub(crate) fn call_tx(to: Address) -> TxEnv {
+    TxBuilder::eip1559()
+        .with_caller(dev_account())
+        .with_gas_limit(100_000)
+        .with_to(to)
+        .build()
+        .unwrap()
+}
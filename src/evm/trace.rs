@@ -0,0 +1,87 @@
+//! EIP-3155 opcode traces, in the format of geth's `evm t8n` and EEST
+
+use super::{EvmTestHarness, HarnessExecutionResult};
+use crate::Result;
+use parking_lot::Mutex;
+use reth::revm::{
+    context::TxEnv, context_interface::result::HaltReason, database_interface::DatabaseCommit,
+    inspector::inspectors::TracerEip3155, primitives::hardfork::SpecId, Inspector,
+};
+use reth_evm::{Database, EvmFactory};
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+/// Writer collecting trace output in memory
+#[derive(Clone, Default)]
+struct TraceBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl TraceBuffer {
+    fn lines(&self) -> Vec<String> {
+        let buf = self.0.lock();
+        String::from_utf8_lossy(&buf)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Write for TraceBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<
+        DB: Database + DatabaseCommit,
+        Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+    > EvmTestHarness<DB, Evm>
+where
+    TracerEip3155: for<'a> Inspector<Evm::Context<&'a mut DB>>,
+{
+    /// Execute a transaction and commit it, writing its EIP-3155 trace to `writer`
+    ///
+    /// One JSON line is written per executed opcode, followed by a summary line.
+    pub fn execute_tx_with_trace_writer(
+        &mut self,
+        tx: TxEnv,
+        writer: impl Write + 'static,
+    ) -> Result<HarnessExecutionResult> {
+        let tracer = TracerEip3155::new(Box::new(writer));
+        self.execute_tx_with_inspector(tx, tracer)
+            .map(|(result, _)| result)
+    }
+
+    /// Execute a transaction and commit it, collecting its EIP-3155 trace into the result
+    pub fn execute_tx_traced(&mut self, tx: TxEnv) -> Result<HarnessExecutionResult> {
+        let buffer = TraceBuffer::default();
+        let result = self.execute_tx_with_trace_writer(tx, buffer.clone())?;
+        Ok(result.with_trace(buffer.lines()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evm::test_utils::{call_tx, dev_harness_with_code, CONTRACT, RETURN_EMPTY};
+
+    #[test]
+    fn test_execute_tx_traced() {
+        let mut harness = dev_harness_with_code(&RETURN_EMPTY);
+        let tx = harness.fill_tx(call_tx(CONTRACT)).unwrap();
+
+        let result = harness.execute_tx_traced(tx).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(result.trace.len(), 4);
+        assert!(result.trace[0].contains("\"pc\":0"));
+        assert!(result.trace[0].contains("\"opName\":\"PUSH1\""));
+        assert!(result.trace[2].contains("\"opName\":\"RETURN\""));
+        assert!(result.trace[3].contains("\"gasUsed\""));
+    }
+}
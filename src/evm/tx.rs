@@ -0,0 +1,264 @@
+//! Fluent transaction builder
+
+use crate::{Error, Result};
+use alloy_eips::{eip2930::AccessList, eip7702::SignedAuthorization};
+use reth::revm::{
+    context::TxEnv,
+    primitives::{Address, Bytes, TxKind, B256, U256},
+};
+
+/// Legacy transaction type
+pub const LEGACY_TX_TYPE: u8 = 0;
+/// EIP-2930 access list transaction type
+pub const EIP2930_TX_TYPE: u8 = 1;
+/// EIP-1559 dynamic fee transaction type
+pub const EIP1559_TX_TYPE: u8 = 2;
+/// EIP-4844 blob transaction type
+pub const EIP4844_TX_TYPE: u8 = 3;
+/// EIP-7702 set code transaction type
+pub const EIP7702_TX_TYPE: u8 = 4;
+
+/// Builder for `TxEnv` that sets the transaction type and validates field combinations
+#[derive(Debug, Clone)]
+pub struct TxBuilder {
+    tx: TxEnv,
+    authorization_list: Vec<SignedAuthorization>,
+}
+
+impl TxBuilder {
+    fn with_tx_type(tx_type: u8) -> Self {
+        Self {
+            tx: TxEnv {
+                tx_type,
+                ..Default::default()
+            },
+            authorization_list: Vec::new(),
+        }
+    }
+
+    /// Start a legacy transaction
+    pub fn legacy() -> Self {
+        Self::with_tx_type(LEGACY_TX_TYPE)
+    }
+
+    /// Start an EIP-2930 access list transaction
+    pub fn eip2930() -> Self {
+        Self::with_tx_type(EIP2930_TX_TYPE)
+    }
+
+    /// Start an EIP-1559 dynamic fee transaction
+    pub fn eip1559() -> Self {
+        Self::with_tx_type(EIP1559_TX_TYPE)
+    }
+
+    /// Start an EIP-4844 blob transaction
+    pub fn eip4844() -> Self {
+        Self::with_tx_type(EIP4844_TX_TYPE)
+    }
+
+    /// Start an EIP-7702 set code transaction
+    pub fn eip7702() -> Self {
+        Self::with_tx_type(EIP7702_TX_TYPE)
+    }
+
+    /// Set the sender
+    pub fn with_caller(mut self, caller: Address) -> Self {
+        self.tx.caller = caller;
+        self
+    }
+
+    /// Call the given address
+    pub fn with_to(mut self, to: Address) -> Self {
+        self.tx.kind = TxKind::Call(to);
+        self
+    }
+
+    /// Create a contract, using the input as init code
+    pub fn with_create(mut self) -> Self {
+        self.tx.kind = TxKind::Create;
+        self
+    }
+
+    /// Set the value transferred
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.tx.value = value;
+        self
+    }
+
+    /// Set the calldata or init code
+    pub fn with_input(mut self, input: impl Into<Bytes>) -> Self {
+        self.tx.data = input.into();
+        self
+    }
+
+    /// Set the nonce
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.tx.nonce = nonce;
+        self
+    }
+
+    /// Set the gas limit
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.tx.gas_limit = gas_limit;
+        self
+    }
+
+    /// Set the gas price (legacy and EIP-2930 transactions)
+    pub fn with_gas_price(mut self, gas_price: u128) -> Self {
+        self.tx.gas_price = gas_price;
+        self
+    }
+
+    /// Set the max fee per gas (EIP-1559 style transactions)
+    pub fn with_max_fee_per_gas(mut self, max_fee_per_gas: u128) -> Self {
+        self.tx.gas_price = max_fee_per_gas;
+        self
+    }
+
+    /// Set the max priority fee per gas (EIP-1559 style transactions)
+    pub fn with_max_priority_fee_per_gas(mut self, max_priority_fee_per_gas: u128) -> Self {
+        self.tx.gas_priority_fee = Some(max_priority_fee_per_gas);
+        self
+    }
+
+    /// Set the chain ID
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.tx.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the access list (all typed transactions)
+    pub fn with_access_list(mut self, access_list: AccessList) -> Self {
+        self.tx.access_list = access_list;
+        self
+    }
+
+    /// Set the blob versioned hashes (EIP-4844 transactions)
+    pub fn with_blob_hashes(mut self, blob_hashes: Vec<B256>) -> Self {
+        self.tx.blob_hashes = blob_hashes;
+        self
+    }
+
+    /// Set the max fee per blob gas (EIP-4844 transactions)
+    pub fn with_max_fee_per_blob_gas(mut self, max_fee_per_blob_gas: u128) -> Self {
+        self.tx.max_fee_per_blob_gas = max_fee_per_blob_gas;
+        self
+    }
+
+    /// Set the authorization list (EIP-7702 transactions)
+    pub fn with_authorization_list(mut self, authorization_list: Vec<SignedAuthorization>) -> Self {
+        self.authorization_list = authorization_list;
+        self
+    }
+
+    /// Validate the field combination and build the `TxEnv`
+    pub fn build(self) -> Result<TxEnv> {
+        let Self {
+            mut tx,
+            authorization_list,
+        } = self;
+
+        if tx.tx_type == LEGACY_TX_TYPE && !tx.access_list.0.is_empty() {
+            return Err(Error::invalid_transaction(
+                "access list requires a typed transaction",
+            ));
+        }
+
+        if tx.tx_type < EIP1559_TX_TYPE && tx.gas_priority_fee.is_some() {
+            return Err(Error::invalid_transaction(
+                "priority fee requires an EIP-1559 style transaction",
+            ));
+        }
+
+        if let Some(priority_fee) = tx.gas_priority_fee {
+            if priority_fee > tx.gas_price {
+                return Err(Error::invalid_transaction(format!(
+                    "priority fee {} exceeds max fee {}",
+                    priority_fee, tx.gas_price
+                )));
+            }
+        }
+
+        if tx.tx_type == EIP4844_TX_TYPE {
+            if tx.blob_hashes.is_empty() {
+                return Err(Error::invalid_transaction(
+                    "blob transaction requires at least one blob hash",
+                ));
+            }
+            if tx.kind.is_create() {
+                return Err(Error::invalid_transaction(
+                    "blob transaction cannot create a contract",
+                ));
+            }
+        } else if !tx.blob_hashes.is_empty() || tx.max_fee_per_blob_gas != 0 {
+            return Err(Error::invalid_transaction(
+                "blob fields require an EIP-4844 transaction",
+            ));
+        }
+
+        if tx.tx_type == EIP7702_TX_TYPE {
+            if authorization_list.is_empty() {
+                return Err(Error::invalid_transaction(
+                    "set code transaction requires a non-empty authorization list",
+                ));
+            }
+            if tx.kind.is_create() {
+                return Err(Error::invalid_transaction(
+                    "set code transaction cannot create a contract",
+                ));
+            }
+        } else if !authorization_list.is_empty() {
+            return Err(Error::invalid_transaction(
+                "authorization list requires an EIP-7702 transaction",
+            ));
+        }
+
+        tx.set_signed_authorization(authorization_list);
+
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_typed_transactions() {
+        let tx = TxBuilder::eip1559()
+            .with_to(Address::with_last_byte(1))
+            .with_max_fee_per_gas(2_000_000_000)
+            .with_max_priority_fee_per_gas(1_000_000_000)
+            .build()
+            .unwrap();
+
+        assert_eq!(tx.tx_type, EIP1559_TX_TYPE);
+        assert_eq!(tx.gas_priority_fee, Some(1_000_000_000));
+
+        let tx = TxBuilder::eip4844()
+            .with_to(Address::with_last_byte(1))
+            .with_blob_hashes(vec![B256::with_last_byte(1)])
+            .with_max_fee_per_blob_gas(1)
+            .build()
+            .unwrap();
+
+        assert_eq!(tx.tx_type, EIP4844_TX_TYPE);
+    }
+
+    #[test]
+    fn test_rejects_invalid_field_combinations() {
+        assert!(TxBuilder::legacy()
+            .with_blob_hashes(vec![B256::with_last_byte(1)])
+            .build()
+            .is_err());
+        assert!(TxBuilder::eip4844().with_create().build().is_err());
+        assert!(TxBuilder::eip7702()
+            .with_to(Address::with_last_byte(1))
+            .build()
+            .is_err());
+        assert!(TxBuilder::eip2930()
+            .with_max_priority_fee_per_gas(1)
+            .build()
+            .is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Fixture file formats
 
 use reth::revm::primitives::{Address, Bytes, B256, U256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[cfg(feature = "fixtures")]
 use serde::{Deserialize, Serialize};
@@ -33,7 +33,7 @@ pub struct BlockFixture {
 }
 
 /// Account state for fixtures
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "fixtures", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountState {
     /// Account balance
@@ -43,7 +43,7 @@ pub struct AccountState {
     /// Code hash
     pub code: Option<Bytes>,
     /// Storage slots
-    pub storage: HashMap<U256, U256>,
+    pub storage: BTreeMap<U256, U256>,
 }
 
 /// A test vector containing multiple blocks
@@ -61,3 +61,42 @@ pub struct TestVector {
     /// Blocks to test
     pub blocks: Vec<BlockFixture>,
 }
+
+/// Recorded JSON-RPC responses for replaying a forked state without network access
+#[cfg(feature = "fixtures")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RpcCassette {
+    /// Block number the state was forked at
+    pub block_number: u64,
+    /// Responses keyed by method and parameters
+    pub responses: BTreeMap<String, serde_json::Value>,
+}
+
+/// Full harness state in anvil's `dumpState` format
+///
+/// Accounts are [`AccountState`]s by default; `StateDumpDb` reads them as raw JSON to
+/// decode each account lazily.
+#[cfg(feature = "fixtures")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateDump<A = AccountState> {
+    /// Block environment at the time of the dump
+    pub block: Option<reth::revm::context::BlockEnv>,
+    /// Accounts with their code and storage
+    pub accounts: BTreeMap<Address, A>,
+    /// Current block number
+    pub best_block_number: Option<u64>,
+}
+
+#[cfg(feature = "fixtures")]
+impl StateDump {
+    /// Parse a dump from JSON
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| crate::Error::fixture(format!("Failed to parse state dump: {}", e)))
+    }
+
+    /// Render the dump as JSON
+    pub fn to_json(&self) -> crate::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| crate::Error::fixture(e.to_string()))
+    }
+}
@@ -0,0 +1,172 @@
+//! Forge-style gas snapshots with regression detection
+
+use super::FixtureManager;
+use crate::{Error, Result};
+use std::collections::BTreeMap;
+
+/// Environment variable that switches new gas snapshots to update mode when set
+pub const UPDATE_GAS_SNAPSHOTS_ENV: &str = "UPDATE_GAS_SNAPSHOTS";
+
+/// How recorded gas is treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasSnapshotMode {
+    /// Compare against the committed snapshot file
+    Check,
+    /// Record without comparing, so the file can be rewritten
+    Update,
+}
+
+/// Named gas measurements compared against a committed snapshot file
+#[derive(Debug)]
+pub struct GasSnapshots {
+    manager: FixtureManager,
+    file: String,
+    committed: BTreeMap<String, u64>,
+    recorded: BTreeMap<String, u64>,
+    tolerance_percent: u64,
+    mode: GasSnapshotMode,
+    warn_only: bool,
+    mismatches: Vec<String>,
+}
+
+impl GasSnapshots {
+    /// Load the committed snapshots from `file` in the fixtures directory
+    ///
+    /// Starts in update mode if `UPDATE_GAS_SNAPSHOTS` is set, otherwise in check mode.
+    pub fn new(manager: FixtureManager, file: impl Into<String>) -> Result<Self> {
+        let file = file.into();
+        let committed = manager.load_gas_snapshot(&file)?;
+        let mode = if std::env::var_os(UPDATE_GAS_SNAPSHOTS_ENV).is_some() {
+            GasSnapshotMode::Update
+        } else {
+            GasSnapshotMode::Check
+        };
+
+        Ok(Self {
+            manager,
+            file,
+            committed,
+            recorded: BTreeMap::new(),
+            tolerance_percent: 0,
+            mode,
+            warn_only: false,
+            mismatches: Vec::new(),
+        })
+    }
+
+    /// Allow gas to drift by up to `percent` of the committed value
+    pub fn with_tolerance(mut self, percent: u64) -> Self {
+        self.tolerance_percent = percent;
+        self
+    }
+
+    /// Set the mode
+    pub fn with_mode(mut self, mode: GasSnapshotMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Collect mismatches in [`Self::mismatches`] instead of failing
+    pub fn with_warn_only(mut self) -> Self {
+        self.warn_only = true;
+        self
+    }
+
+    /// Record the gas used under `name`, checking it against the committed value
+    pub fn record(&mut self, name: &str, gas_used: u64) -> Result<()> {
+        self.recorded.insert(name.to_string(), gas_used);
+
+        if self.mode == GasSnapshotMode::Update {
+            return Ok(());
+        }
+
+        let mismatch = match self.committed.get(name) {
+            None => Some(format!(
+                "{}: no committed value in {}, rerun with {} set to record it",
+                name, self.file, UPDATE_GAS_SNAPSHOTS_ENV
+            )),
+            Some(&expected) => {
+                let allowed = expected.saturating_mul(self.tolerance_percent) / 100;
+                (gas_used.abs_diff(expected) > allowed).then(|| {
+                    format!(
+                        "{}: gas used {} differs from committed {} by {:+} (tolerance {}%)",
+                        name,
+                        gas_used,
+                        expected,
+                        gas_used as i128 - expected as i128,
+                        self.tolerance_percent
+                    )
+                })
+            }
+        };
+
+        match mismatch {
+            Some(message) if self.warn_only => {
+                self.mismatches.push(message);
+                Ok(())
+            }
+            Some(message) => Err(Error::gas_snapshot(message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the values recorded so far
+    pub fn recorded(&self) -> &BTreeMap<String, u64> {
+        &self.recorded
+    }
+
+    /// Get the mismatches collected in warn-only mode, in recording order
+    pub fn mismatches(&self) -> &[String] {
+        &self.mismatches
+    }
+
+    /// Rewrite the snapshot file with the recorded values in update mode
+    ///
+    /// Committed entries that were not recorded are kept, so filtered test runs don't
+    /// drop them. Does nothing in check mode.
+    pub fn write(&self) -> Result<()> {
+        if self.mode == GasSnapshotMode::Check {
+            return Ok(());
+        }
+
+        let mut snapshots = self.committed.clone();
+        snapshots.extend(self.recorded.clone());
+        self.manager.save_gas_snapshot(&self.file, &snapshots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_then_check_with_tolerance() {
+        let dir = std::env::temp_dir().join(format!("gas-snapshot-{}", std::process::id()));
+        let manager = || FixtureManager::new(&dir);
+
+        let mut snapshots = GasSnapshots::new(manager(), ".gas-snapshot")
+            .unwrap()
+            .with_mode(GasSnapshotMode::Update);
+        snapshots.record("transfer", 21_000).unwrap();
+        snapshots.write().unwrap();
+
+        let mut snapshots = GasSnapshots::new(manager(), ".gas-snapshot")
+            .unwrap()
+            .with_mode(GasSnapshotMode::Check)
+            .with_tolerance(1);
+        assert!(snapshots.record("transfer", 21_200).is_ok());
+        assert!(snapshots.record("transfer", 21_211).is_err());
+        assert!(snapshots.record("unknown", 1).is_err());
+
+        let mut snapshots = GasSnapshots::new(manager(), ".gas-snapshot")
+            .unwrap()
+            .with_mode(GasSnapshotMode::Check)
+            .with_warn_only();
+        assert!(snapshots.record("transfer", 21_000).is_ok());
+        assert!(snapshots.record("transfer", 25_000).is_ok());
+        assert_eq!(snapshots.mismatches().len(), 1);
+        assert!(snapshots.mismatches()[0].starts_with("transfer:"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,14 +1,16 @@
 //! Fixture management utilities
 
 #[cfg(feature = "fixtures")]
-use super::format::{BlockFixture, TestVector};
+use super::format::{BlockFixture, RpcCassette, StateDump, TestVector};
 
-#[cfg(feature = "fixtures")]
 use crate::{Error, Result};
-
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 /// Manages loading and saving test fixtures
+#[derive(Debug, Clone)]
 pub struct FixtureManager {
     fixtures_dir: PathBuf,
 }
@@ -106,6 +108,121 @@ impl FixtureManager {
         Ok(())
     }
 
+    /// Load a recorded RPC cassette, returning `None` if it does not exist
+    #[cfg(feature = "fixtures")]
+    pub fn load_cassette(&self, name: &str) -> Result<Option<RpcCassette>> {
+        let path = self.cassette_path(name);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| Error::fixture(e.to_string()))?;
+
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| Error::fixture(format!("Failed to parse cassette: {}", e)))
+    }
+
+    /// Save a recorded RPC cassette
+    #[cfg(feature = "fixtures")]
+    pub fn save_cassette(&self, name: &str, cassette: &RpcCassette) -> Result<()> {
+        let path = self.cassette_path(name);
+        std::fs::create_dir_all(self.fixtures_dir.join("cassettes"))
+            .map_err(|e| Error::fixture(e.to_string()))?;
+
+        let content =
+            serde_json::to_string_pretty(cassette).map_err(|e| Error::fixture(e.to_string()))?;
+
+        std::fs::write(&path, content).map_err(|e| Error::fixture(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "fixtures")]
+    fn cassette_path(&self, name: &str) -> PathBuf {
+        self.fixtures_dir
+            .join("cassettes")
+            .join(format!("{}.json", name))
+    }
+
+    /// Load a state dump saved with [`Self::save_state_dump`]
+    #[cfg(feature = "fixtures")]
+    pub fn load_state_dump(&self, name: &str) -> Result<StateDump> {
+        let path = self
+            .fixtures_dir
+            .join("states")
+            .join(format!("{}.json", name));
+
+        if !path.exists() {
+            return Err(Error::fixture(format!(
+                "State dump file does not exist: {}",
+                path.display()
+            )));
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| Error::fixture(e.to_string()))?;
+
+        StateDump::from_json(&content)
+    }
+
+    /// Save a state dump under `states/`
+    #[cfg(feature = "fixtures")]
+    pub fn save_state_dump(&self, name: &str, dump: &StateDump) -> Result<()> {
+        let dir = self.fixtures_dir.join("states");
+        std::fs::create_dir_all(&dir).map_err(|e| Error::fixture(e.to_string()))?;
+
+        std::fs::write(dir.join(format!("{}.json", name)), dump.to_json()?)
+            .map_err(|e| Error::fixture(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Load a gas snapshot file, returning no entries if it does not exist
+    ///
+    /// Uses the `forge snapshot` line format: `name (gas: 12345)`.
+    pub fn load_gas_snapshot(&self, file: &str) -> Result<BTreeMap<String, u64>> {
+        let path = self.fixtures_dir.join(file);
+
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| Error::fixture(e.to_string()))?;
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.strip_suffix(')')
+                    .and_then(|line| line.rsplit_once(" (gas: "))
+                    .and_then(|(name, gas)| Some((name.to_string(), gas.parse().ok()?)))
+                    .ok_or_else(|| {
+                        Error::fixture(format!(
+                            "Malformed gas snapshot line in {}: {}",
+                            path.display(),
+                            line
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Save a gas snapshot file, sorted by name
+    pub fn save_gas_snapshot(&self, file: &str, snapshots: &BTreeMap<String, u64>) -> Result<()> {
+        std::fs::create_dir_all(&self.fixtures_dir).map_err(|e| Error::fixture(e.to_string()))?;
+
+        let content: String = snapshots
+            .iter()
+            .map(|(name, gas)| format!("{} (gas: {})\n", name, gas))
+            .collect();
+
+        std::fs::write(self.fixtures_dir.join(file), content)
+            .map_err(|e| Error::fixture(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Get the fixtures directory
     pub fn fixtures_dir(&self) -> &Path {
         &self.fixtures_dir
@@ -1,7 +1,11 @@
 //! Test fixture management
 
 mod format;
+mod gas_snapshot;
 mod manager;
 
-pub use format::{BlockFixture, TestVector};
+pub use format::{AccountState, BlockFixture, TestVector};
+#[cfg(feature = "fixtures")]
+pub use format::{RpcCassette, StateDump};
+pub use gas_snapshot::{GasSnapshotMode, GasSnapshots, UPDATE_GAS_SNAPSHOTS_ENV};
 pub use manager::FixtureManager;
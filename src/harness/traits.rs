@@ -1,6 +1,8 @@
 //! Core traits for testable components
 
+use crate::evm::dev_chain_spec;
 use reth_chainspec::{ChainSpec, EthChainSpec};
+use std::sync::Arc;
 
 #[cfg(feature = "engine")]
 use reth_node_api::FullNodeComponents;
@@ -23,7 +25,7 @@ pub trait TestableChainSpec: EthChainSpec + Clone + Send + Sync + 'static {
 
 impl TestableChainSpec for ChainSpec {
     fn test_spec() -> Self {
-        // Return a basic mainnet-like spec for testing
-        ChainSpec::default()
+        // Mainnet with every fork up to Cancun active, like the dev harness
+        Arc::unwrap_or_clone(dev_chain_spec())
     }
 }
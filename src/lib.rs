@@ -33,6 +33,7 @@
 //!
 //! - [`harness`]: Core test context and builder abstractions
 //! - [`evm`]: EVM-specific testing utilities
+//! - [`db`]: Database backends for captured chain state
 //! - [`engine`]: Engine API testing without network overhead
 //! - [`fixtures`]: Test data and fixture management
 //! - [`presets`]: Common test scenarios
@@ -40,6 +41,7 @@
 //! - [`rpc`]: RPC testing utilities
 
 pub mod consensus;
+pub mod db;
 pub mod engine;
 pub mod evm;
 pub mod fixtures;
@@ -62,6 +64,10 @@ pub enum Error {
     #[error("EVM execution failed: {0}")]
     EvmExecution(String),
 
+    /// Invalid transaction construction
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+
     /// Consensus validation error
     #[error("Consensus validation failed: {0}")]
     Consensus(String),
@@ -74,6 +80,10 @@ pub enum Error {
     #[error("Fixture error: {0}")]
     Fixture(String),
 
+    /// Gas used drifted from a committed gas snapshot
+    #[error("Gas snapshot mismatch: {0}")]
+    GasSnapshot(String),
+
     /// RPC error
     #[error("RPC error: {0}")]
     Rpc(String),
@@ -93,6 +103,11 @@ impl Error {
         Self::EvmExecution(msg.into())
     }
 
+    /// Create a new invalid transaction error
+    pub fn invalid_transaction(msg: impl Into<String>) -> Self {
+        Self::InvalidTransaction(msg.into())
+    }
+
     /// Create a new consensus error
     pub fn consensus(msg: impl Into<String>) -> Self {
         Self::Consensus(msg.into())
@@ -107,4 +122,9 @@ impl Error {
     pub fn fixture(msg: impl Into<String>) -> Self {
         Self::Fixture(msg.into())
     }
+
+    /// Create a new gas snapshot mismatch error
+    pub fn gas_snapshot(msg: impl Into<String>) -> Self {
+        Self::GasSnapshot(msg.into())
+    }
 }
@@ -1,34 +1,41 @@
 //! Fork transition test scenarios
 
-use crate::{evm::EvmTestHarness, Result};
-use reth::revm::{context::TxEnv, database_interface::DatabaseCommit, primitives::hardfork::SpecId};
+use crate::{evm::EvmTestHarness, Error, Result};
+use reth::revm::{
+    context::TxEnv, context_interface::result::HaltReason, database_interface::DatabaseCommit,
+    primitives::hardfork::SpecId,
+};
 use reth_evm::{Database, EvmFactory};
 
 /// Test fork transition by executing transactions before and after
-pub fn test_fork_transition<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>(
+pub fn test_fork_transition<
+    DB: Database + DatabaseCommit,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+>(
     harness: &mut EvmTestHarness<DB, Evm>,
     fork_block: u64,
-    _pre_fork_spec: SpecId,
-    _post_fork_spec: SpecId,
+    pre_fork_spec: SpecId,
+    post_fork_spec: SpecId,
 ) -> Result<()> {
     // Test at block before fork
     harness.set_block_number(fork_block - 1);
+    expect_spec(harness, pre_fork_spec)?;
 
     // TODO: Execute test transactions at pre-fork spec
 
     // Test at fork block
     harness.set_block_number(fork_block);
+    expect_spec(harness, post_fork_spec)?;
 
     // TODO: Execute test transactions at post-fork spec
 
-    // Verify fork activated correctly
     Ok(())
 }
 
 /// Test that features are disabled before fork
 pub fn test_feature_disabled_pre_fork<
     DB: Database + DatabaseCommit,
-    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
 >(
     harness: &mut EvmTestHarness<DB, Evm>,
     fork_block: u64,
@@ -43,7 +50,7 @@ pub fn test_feature_disabled_pre_fork<
 /// Test that features are enabled after fork
 pub fn test_feature_enabled_post_fork<
     DB: Database + DatabaseCommit,
-    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
 >(
     harness: &mut EvmTestHarness<DB, Evm>,
     fork_block: u64,
@@ -54,3 +61,22 @@ pub fn test_feature_enabled_post_fork<
 
     Ok(())
 }
+
+/// Check that the harness resolved the expected spec at its current block
+fn expect_spec<
+    DB: Database + DatabaseCommit,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+>(
+    harness: &EvmTestHarness<DB, Evm>,
+    expected: SpecId,
+) -> Result<()> {
+    if harness.spec_id() != expected {
+        return Err(Error::consensus(format!(
+            "Expected spec {:?} at block {}, got {:?}",
+            expected,
+            harness.block_number(),
+            harness.spec_id()
+        )));
+    }
+    Ok(())
+}
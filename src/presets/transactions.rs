@@ -1,50 +1,50 @@
 //! Common transaction test scenarios
 
-use crate::{evm::EvmTestHarness, Error, Result};
+use crate::{
+    evm::{EvmTestHarness, TxBuilder},
+    Error, Result,
+};
 use reth::revm::{
     context::TxEnv,
+    context_interface::result::HaltReason,
     database_interface::DatabaseCommit,
-    primitives::{hardfork::SpecId, Address, Bytes, TxKind, U256},
+    primitives::{hardfork::SpecId, Address, Bytes, U256},
 };
 use reth_evm::{Database, EvmFactory};
 
 /// Test EIP-1559 transaction execution
 pub fn test_eip1559_transaction<
     DB: Database + DatabaseCommit,
-    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
 >(
     harness: &mut EvmTestHarness<DB, Evm>,
 ) -> Result<()> {
-    let tx = TxEnv {
-        caller: Address::ZERO,
-        gas_limit: 21_000,
-        gas_price: 1_000_000_000u128, // 1 gwei
-        gas_priority_fee: Some(1_000_000_000u128),
-        kind: TxKind::Call(Address::with_last_byte(1)),
-        value: U256::from(1_000_000_000_000_000_000u64), // 1 ETH
-        data: Bytes::new(),
-        nonce: 0,
-        chain_id: Some(harness.chain_id()),
-        access_list: Default::default(),
-        blob_hashes: vec![],
-        max_fee_per_blob_gas: 0,
-        authorization_list: vec![],
-        tx_type: 2, // EIP-1559
-    };
+    let tx = TxBuilder::eip1559()
+        .with_caller(Address::ZERO)
+        .with_gas_limit(21_000)
+        .with_max_fee_per_gas(1_000_000_000u128) // 1 gwei
+        .with_max_priority_fee_per_gas(1_000_000_000u128)
+        .with_to(Address::with_last_byte(1))
+        .with_value(U256::from(1_000_000_000_000_000_000u64)) // 1 ETH
+        .build()?;
 
-    let result = harness.execute_tx(tx)?;
+    let result = harness.send_tx(tx)?;
 
     if result.is_success() {
         Ok(())
     } else {
-        Err(Error::evm_execution(
-            result.revert_reason().unwrap_or("Transaction reverted"),
-        ))
+        Err(Error::evm_execution(result.revert_reason().map_or_else(
+            || "Transaction reverted".to_string(),
+            ToString::to_string,
+        )))
     }
 }
 
 /// Test that a transaction type is rejected
-pub fn test_tx_rejection<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>(
+pub fn test_tx_rejection<
+    DB: Database + DatabaseCommit,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+>(
     harness: &mut EvmTestHarness<DB, Evm>,
     tx: TxEnv,
 ) -> Result<()> {
@@ -60,102 +60,79 @@ pub fn test_tx_rejection<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = S
 }
 
 /// Test basic value transfer
-pub fn test_value_transfer<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>(
+pub fn test_value_transfer<
+    DB: Database + DatabaseCommit,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+>(
     harness: &mut EvmTestHarness<DB, Evm>,
     from: Address,
     to: Address,
     value: U256,
 ) -> Result<()> {
-    let tx = TxEnv {
-        caller: from,
-        gas_limit: 21_000,
-        gas_price: 1_000_000_000u128,
-        kind: TxKind::Call(to),
-        value,
-        data: Bytes::new(),
-        nonce: 0,
-        chain_id: Some(harness.chain_id()),
-        access_list: Default::default(),
-        gas_priority_fee: Default::default(),
-        blob_hashes: vec![],
-        max_fee_per_blob_gas: 0,
-        authorization_list: vec![],
-        tx_type: 0,
-    };
+    let tx = TxBuilder::legacy()
+        .with_caller(from)
+        .with_gas_limit(21_000)
+        .with_gas_price(1_000_000_000u128)
+        .with_to(to)
+        .with_value(value)
+        .build()?;
 
-    let result = harness.execute_tx(tx)?;
+    let result = harness.send_tx(tx)?;
 
     if result.is_success() {
         Ok(())
     } else {
-        Err(Error::evm_execution(
-            result.revert_reason().unwrap_or("Value transfer failed"),
-        ))
+        Err(Error::evm_execution(result.revert_reason().map_or_else(
+            || "Value transfer failed".to_string(),
+            ToString::to_string,
+        )))
     }
 }
 
 /// Test contract deployment
 pub fn test_contract_deployment<
     DB: Database + DatabaseCommit,
-    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
 >(
     harness: &mut EvmTestHarness<DB, Evm>,
     bytecode: Bytes,
 ) -> Result<Address> {
-    let tx = TxEnv {
-        caller: Address::ZERO,
-        gas_limit: 10_000_000,
-        gas_price: 1_000_000_000u128,
-        kind: TxKind::Create,
-        value: U256::ZERO,
-        data: bytecode,
-        nonce: 0,
-        chain_id: Some(harness.chain_id()),
-        access_list: Default::default(),
-        gas_priority_fee: Default::default(),
-        blob_hashes: vec![],
-        max_fee_per_blob_gas: 0,
-        authorization_list: vec![],
-        tx_type: 0,
-    };
+    let tx = TxBuilder::legacy()
+        .with_caller(Address::ZERO)
+        .with_gas_limit(10_000_000)
+        .with_gas_price(1_000_000_000u128)
+        .with_create()
+        .with_input(bytecode)
+        .build()?;
 
-    let result = harness.execute_tx(tx)?;
+    let result = harness.send_tx(tx)?;
 
-    if result.is_success() {
-        // TODO: Extract deployed contract address from result
-        Ok(Address::ZERO)
+    if let Some(address) = result.created_address() {
+        Ok(address)
     } else {
-        Err(Error::evm_execution(
-            result
-                .revert_reason()
-                .unwrap_or("Contract deployment failed"),
-        ))
+        Err(Error::evm_execution(result.revert_reason().map_or_else(
+            || "Contract deployment failed".to_string(),
+            ToString::to_string,
+        )))
     }
 }
 
 /// Test gas limit enforcement
-pub fn test_gas_limit<DB: Database + DatabaseCommit, Evm: EvmFactory<Spec = SpecId, Tx = TxEnv>>(
+pub fn test_gas_limit<
+    DB: Database + DatabaseCommit,
+    Evm: EvmFactory<Spec = SpecId, Tx = TxEnv, HaltReason: TryInto<HaltReason>>,
+>(
     harness: &mut EvmTestHarness<DB, Evm>,
     gas_limit: u64,
 ) -> Result<()> {
-    let tx = TxEnv {
-        caller: Address::ZERO,
-        gas_limit,
-        gas_price: 1_000_000_000u128,
-        kind: TxKind::Call(Address::with_last_byte(1)),
-        value: U256::ZERO,
-        data: Bytes::new(),
-        nonce: 0,
-        chain_id: Some(harness.chain_id()),
-        access_list: Default::default(),
-        gas_priority_fee: Default::default(),
-        blob_hashes: vec![],
-        max_fee_per_blob_gas: 0,
-        authorization_list: vec![],
-        tx_type: 0,
-    };
+    let tx = TxBuilder::legacy()
+        .with_caller(Address::ZERO)
+        .with_gas_limit(gas_limit)
+        .with_gas_price(1_000_000_000u128)
+        .with_to(Address::with_last_byte(1))
+        .build()?;
 
-    let result = harness.execute_tx(tx)?;
+    let result = harness.send_tx(tx)?;
 
     // Check that gas used doesn't exceed gas limit
     if result.gas_used <= gas_limit {